@@ -0,0 +1,265 @@
+use crate::error::Error;
+#[cfg(feature = "serde")]
+use crate::interning::{extern_rows, extern_values};
+use crate::interning::{hypothesis_table, intern_rows, intern_values};
+use crate::precision::{cast_rows, cast_values};
+use crate::results::Results;
+use crate::scratch::Scratch;
+use crate::unseen::UnseenPolicy;
+use num_traits::Float;
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasher, Hash};
+
+#[derive(Clone, Debug)]
+pub struct BernoulliClassifier<D, H, F = f64, S = RandomState>
+where
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    F: Float,
+    S: BuildHasher,
+{
+    // each hypothesis, indexed by its id (see interning.rs)
+    hypotheses: Vec<H>,
+    // probability of each hypothesis, by id
+    log_priors: Vec<F>,
+    // log2(P(D|H)) - log2(1 - P(D|H)) for values seen with H during training, with each H as an
+    // id
+    log_ratios: HashMap<D, Vec<(u32, F)>, S>,
+    // log2(P(D|H)) - log2(1 - P(D|H)) assumed for values not seen with H during training, by id
+    log_unseen_ratios: Vec<F>,
+    // probability of a document given H, when no value in the vocabulary is present, by id
+    log_absent: Vec<F>,
+}
+
+impl<D, H, F, S> PartialEq for BernoulliClassifier<D, H, F, S>
+where
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    F: Float,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.hypotheses == other.hypotheses
+            && self.log_priors == other.log_priors
+            && self.log_ratios == other.log_ratios
+            && self.log_unseen_ratios == other.log_unseen_ratios
+            && self.log_absent == other.log_absent
+    }
+}
+
+impl<D, H, F, S> BernoulliClassifier<D, H, F, S>
+where
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    F: Float,
+    S: BuildHasher + Default,
+{
+    /// Create a new Classifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `log_priors` - probability of hypothesis
+    /// * `log_ratios` - log odds of presence of data given hypothesis
+    /// * `log_unseen_ratios` - log odds of presence of unseen data given hypothesis
+    /// * `log_absent` - probability of absence of all data given hypothesis
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(BernoulliClassifier)` - the classifier.
+    /// * `Err(Error::InconsistentModel)` - a log odds refers to a hypothesis with no probability,
+    ///   a hypothesis has no log odds for unseen data or no probability of absence, or a value
+    ///   is not finite.
+    ///
+    pub(crate) fn new(
+        log_priors: HashMap<H, F, S>,
+        log_ratios: HashMap<D, Vec<(H, F)>, S>,
+        log_unseen_ratios: HashMap<H, F, S>,
+        log_absent: HashMap<H, F, S>,
+    ) -> Result<Self, Error> {
+        let (hypotheses, ids) = hypothesis_table(&log_priors);
+        let log_ratios = intern_rows(log_ratios, &ids).ok_or(Error::InconsistentModel)?;
+        // Every per-hypothesis map has exactly the hypotheses of the priors.
+        let per_hypothesis = |map: &HashMap<H, F, S>| {
+            if map.len() == hypotheses.len() {
+                intern_values(map, &hypotheses)
+            } else {
+                None
+            }
+        };
+        let log_unseen_ratios =
+            per_hypothesis(&log_unseen_ratios).ok_or(Error::InconsistentModel)?;
+        let log_absent = per_hypothesis(&log_absent).ok_or(Error::InconsistentModel)?;
+        let log_priors = intern_values(&log_priors, &hypotheses).unwrap();
+
+        let finite = log_priors
+            .iter()
+            .chain(&log_unseen_ratios)
+            .chain(&log_absent)
+            .chain(log_ratios.values().flatten().map(|(_, r)| r))
+            .all(|p| p.is_finite());
+        if !finite {
+            return Err(Error::InconsistentModel);
+        }
+        Ok(BernoulliClassifier {
+            hypotheses,
+            log_priors,
+            log_ratios,
+            log_unseen_ratios,
+            log_absent,
+        })
+    }
+
+    /// Convert the probabilities of the Classifier to another floating point type, for example
+    /// `f32` to halve the memory used by the model.
+    ///
+    /// # Return Value
+    ///
+    /// * `BernoulliClassifier` type
+    ///
+    pub fn cast<G: Float>(&self) -> BernoulliClassifier<D, H, G, S> {
+        BernoulliClassifier {
+            hypotheses: self.hypotheses.clone(),
+            log_priors: cast_values(&self.log_priors),
+            log_ratios: cast_rows(&self.log_ratios),
+            log_unseen_ratios: cast_values(&self.log_unseen_ratios),
+            log_absent: cast_values(&self.log_absent),
+        }
+    }
+
+    /// Classify an unknown document.
+    ///
+    /// Each distinct value in the vocabulary contributes to the result whether it is present or
+    /// absent. Repeated values count once, and values not in the vocabulary are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `document` - a stream of data representing a single document.
+    ///
+    /// # Return Value
+    ///
+    /// * `Results` type
+    ///
+    pub fn classify(&self, document: &mut dyn Iterator<Item = D>) -> Results<H, F> {
+        self.score::<D, D>(document)
+    }
+
+    /// Classify an unknown document of borrowed values, for example `&str` values when the data
+    /// type is `String`, without converting each value to the data type.
+    ///
+    /// # Arguments
+    ///
+    /// * `document` - a stream of borrowed data representing a single document.
+    ///
+    /// # Return Value
+    ///
+    /// * `Results` type
+    ///
+    pub fn classify_borrowed<'q, Q>(
+        &self,
+        document: &mut dyn Iterator<Item = &'q Q>,
+    ) -> Results<H, F>
+    where
+        D: Borrow<Q>,
+        Q: Eq + Hash + ?Sized + 'q,
+    {
+        self.score::<Q, &Q>(document)
+    }
+
+    // Classify a document of values that can be borrowed in the same form as the data type.
+    fn score<Q, T>(&self, document: &mut dyn Iterator<Item = T>) -> Results<H, F>
+    where
+        D: Borrow<Q>,
+        T: Borrow<Q> + Eq + Hash,
+        Q: Eq + Hash + ?Sized,
+    {
+        // Start from the probability of a document with no values present, then correct for
+        // each value that is present, by hypothesis id.
+        let mut scratch = Scratch::new();
+        scratch.reset_to(&self.log_absent);
+        let present: HashSet<T, S> = document.collect();
+        for (i, d) in present.iter().enumerate() {
+            let found = match self.log_ratios.get(d.borrow()) {
+                Some(found) => found,
+                None => continue,
+            };
+            // A hypothesis that did not see a present value is always corrected by its unseen
+            // ratio.
+            scratch.add(
+                i,
+                found.iter().copied(),
+                &self.log_unseen_ratios,
+                UnseenPolicy::Smoothed,
+                |r| r,
+            );
+        }
+
+        let mut results = Results::default();
+        scratch.posteriors_into(&self.hypotheses, &self.log_priors, &mut results);
+        results
+    }
+}
+
+// The serialized form of a BernoulliClassifier, which refers to hypotheses by value.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(bound(
+    serialize = "D: serde::Serialize, H: serde::Serialize, F: serde::Serialize",
+    deserialize = "D: serde::Deserialize<'de>, H: serde::Deserialize<'de>, \
+                   F: serde::Deserialize<'de>, S: BuildHasher + Default"
+))]
+struct BernoulliClassifierEntries<D, H, F, S>
+where
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    S: BuildHasher,
+{
+    #[serde(with = "crate::serialization::entries")]
+    log_priors: HashMap<H, F, S>,
+    #[serde(with = "crate::serialization::entries")]
+    log_ratios: HashMap<D, Vec<(H, F)>, S>,
+    #[serde(with = "crate::serialization::entries")]
+    log_unseen_ratios: HashMap<H, F, S>,
+    #[serde(with = "crate::serialization::entries")]
+    log_absent: HashMap<H, F, S>,
+}
+
+#[cfg(feature = "serde")]
+impl<D, H, F, S> serde::Serialize for BernoulliClassifier<D, H, F, S>
+where
+    D: Clone + Eq + Hash + serde::Serialize,
+    H: Clone + Eq + Hash + serde::Serialize,
+    F: Float + serde::Serialize,
+    S: BuildHasher + Default,
+{
+    fn serialize<T: serde::Serializer>(&self, serializer: T) -> Result<T::Ok, T::Error> {
+        BernoulliClassifierEntries {
+            log_priors: extern_values::<_, _, S>(&self.log_priors, &self.hypotheses),
+            log_ratios: extern_rows(&self.log_ratios, &self.hypotheses),
+            log_unseen_ratios: extern_values(&self.log_unseen_ratios, &self.hypotheses),
+            log_absent: extern_values(&self.log_absent, &self.hypotheses),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, D, H, F, S> serde::Deserialize<'de> for BernoulliClassifier<D, H, F, S>
+where
+    D: Clone + Eq + Hash + serde::Deserialize<'de>,
+    H: Clone + Eq + Hash + serde::Deserialize<'de>,
+    F: Float + serde::Deserialize<'de>,
+    S: BuildHasher + Default,
+{
+    fn deserialize<T: serde::Deserializer<'de>>(deserializer: T) -> Result<Self, T::Error> {
+        let entries = BernoulliClassifierEntries::<D, H, F, S>::deserialize(deserializer)?;
+        BernoulliClassifier::new(
+            entries.log_priors,
+            entries.log_ratios,
+            entries.log_unseen_ratios,
+            entries.log_absent,
+        )
+        .map_err(serde::de::Error::custom)
+    }
+}
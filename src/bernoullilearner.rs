@@ -0,0 +1,183 @@
+use crate::bernoulliclassifier::BernoulliClassifier;
+use crate::error::Error;
+use crate::likelihoods::{presence_likelihoods, priors};
+use crate::smoothing::Smoothing;
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::hash::{BuildHasher, Hash};
+use std::iter::Iterator;
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "D: serde::Serialize, H: serde::Serialize",
+        deserialize = "D: serde::Deserialize<'de>, H: serde::Deserialize<'de>, \
+                       S: BuildHasher + Default"
+    ))
+)]
+pub struct BernoulliLearner<D, H, S = RandomState>
+where
+    D: Clone + Debug + Eq + Hash,
+    H: Clone + Debug + Eq + Hash,
+    S: BuildHasher,
+{
+    // The count of |h| is the number of documents, and the count of |(d,h)| is the number of
+    // documents containing d, regardless of how many times d occurs in each one.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::counts"))]
+    count_hypotheses: HashMap<H, f64, S>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::counts"))]
+    count_joint: HashMap<(D, H), f64, S>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::count"))]
+    count_total: f64,
+}
+
+impl<D: Clone + Debug + Eq + Hash, H: Clone + Debug + Eq + Hash> Default
+    for BernoulliLearner<D, H>
+{
+    fn default() -> Self {
+        Self::with_default_hasher()
+    }
+}
+
+impl<D, H, S> PartialEq for BernoulliLearner<D, H, S>
+where
+    D: Clone + Debug + Eq + Hash,
+    H: Clone + Debug + Eq + Hash,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.count_hypotheses == other.count_hypotheses
+            && self.count_joint == other.count_joint
+            && self.count_total == other.count_total
+    }
+}
+
+impl<D, H, S> BernoulliLearner<D, H, S>
+where
+    D: Clone + Debug + Eq + Hash,
+    H: Clone + Debug + Eq + Hash,
+    S: BuildHasher + Default,
+{
+    /// Create a Learner whose maps use the hasher type `S`, for example `FxBuildHasher`.
+    ///
+    /// Unlike `HashMap::with_hasher`, this takes no hasher instance: every map, including the
+    /// maps of the Classifiers made by the Learner, is created with `S::default()`, so the
+    /// hasher is chosen by its type and cannot be seeded per instance.
+    ///
+    /// `default()` creates a Learner that uses the standard library's hasher.
+    ///
+    pub fn with_default_hasher() -> Self {
+        BernoulliLearner {
+            count_hypotheses: HashMap::default(),
+            count_joint: HashMap::default(),
+            count_total: 0.0,
+        }
+    }
+
+    /// Update the Learner with a single document for a single hypothesis.
+    ///
+    /// # Arguments
+    ///
+    /// * `document` - a stream of data representing a single document.
+    /// * `hypothesis` - the target hypothesis/label/category/classification for the data.
+    ///
+    pub fn update(&mut self, document: &mut dyn Iterator<Item = D>, hypothesis: H) -> &mut Self {
+        let present: HashSet<D, S> = document.collect();
+        for d in present {
+            *self
+                .count_joint
+                .entry((d, hypothesis.clone()))
+                .or_insert(0.0) += 1.0;
+        }
+        *self.count_hypotheses.entry(hypothesis).or_insert(0.0) += 1.0;
+        self.count_total += 1.0;
+        self
+    }
+
+    /// Make a classifier based on a snapshot of the current Learner's training.
+    ///
+    /// Laplace smoothing is applied, see `make_classifier_with_smoothing`.
+    ///
+    /// # Return Value
+    ///
+    /// * `BernoulliClassifier` type
+    ///
+    pub fn make_classifier(&mut self) -> BernoulliClassifier<D, H, f64, S> {
+        self.make_classifier_with_smoothing(Smoothing::Laplace)
+    }
+
+    /// Make a classifier based on a snapshot of the current Learner's training, estimating the
+    /// probability of presence of each value with the given smoothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `smoothing` - the smoothing applied to the probability of presence of each value
+    ///
+    /// # Return Value
+    ///
+    /// * `BernoulliClassifier` type
+    ///
+    /// # Panics
+    ///
+    /// * if the smoothing alpha is not positive and finite, or if the Learner was deserialised
+    ///   from corrupt data, see `try_make_classifier_with_smoothing`.
+    ///
+    pub fn make_classifier_with_smoothing(
+        &mut self,
+        smoothing: Smoothing,
+    ) -> BernoulliClassifier<D, H, f64, S> {
+        self.classifier(smoothing).unwrap()
+    }
+
+    /// Make a classifier based on a snapshot of the current Learner's training, failing if the
+    /// Learner has no training data or is inconsistent.
+    ///
+    /// Laplace smoothing is applied, see `try_make_classifier_with_smoothing`.
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(BernoulliClassifier)` - the classifier.
+    /// * `Err(Error::EmptyModel)` - the Learner has no training data.
+    /// * `Err(Error::InconsistentModel)` - the Learner was deserialised from corrupt data.
+    ///
+    pub fn try_make_classifier(&mut self) -> Result<BernoulliClassifier<D, H, f64, S>, Error> {
+        self.try_make_classifier_with_smoothing(Smoothing::Laplace)
+    }
+
+    /// Make a classifier based on a snapshot of the current Learner's training, estimating the
+    /// probability of presence of each value with the given smoothing, failing if the Learner
+    /// has no training data or is inconsistent.
+    ///
+    /// # Arguments
+    ///
+    /// * `smoothing` - the smoothing applied to the probability of presence of each value
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(BernoulliClassifier)` - the classifier.
+    /// * `Err(Error::EmptyModel)` - the Learner has no training data.
+    /// * `Err(Error::InvalidSmoothing)` - the smoothing alpha is not positive and finite.
+    /// * `Err(Error::InconsistentModel)` - the Learner was deserialised from corrupt data.
+    ///
+    pub fn try_make_classifier_with_smoothing(
+        &mut self,
+        smoothing: Smoothing,
+    ) -> Result<BernoulliClassifier<D, H, f64, S>, Error> {
+        if self.count_hypotheses.is_empty() {
+            return Err(Error::EmptyModel);
+        }
+        self.classifier(smoothing)
+    }
+
+    // Make a classifier, failing if the smoothing is invalid or the counts are inconsistent.
+    fn classifier(&self, smoothing: Smoothing) -> Result<BernoulliClassifier<D, H, f64, S>, Error> {
+        smoothing.check()?;
+        let log_priors: HashMap<H, f64, S> = priors(&self.count_hypotheses, self.count_total)?;
+        let (log_ratios, log_unseen_ratios, log_absent) =
+            presence_likelihoods(&self.count_hypotheses, &self.count_joint, smoothing)?;
+        BernoulliClassifier::new(log_priors, log_ratios, log_unseen_ratios, log_absent)
+    }
+}
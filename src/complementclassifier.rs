@@ -0,0 +1,278 @@
+use crate::error::Error;
+use crate::results::Results;
+use crate::scratch::Scratch;
+use crate::streamclassifier::StreamClassifier;
+use crate::unseen::UnseenPolicy;
+use num_traits::Float;
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+
+/// A Complement Naive Bayes classifier, made by `StreamLearner::make_complement_classifier`.
+///
+/// The weight of a value for each hypothesis h is estimated from the training data of every
+/// hypothesis except h, and is positive evidence for h where the value is rare outside h. A
+/// value that a hypothesis h did not see is a value not seen outside h, so it is scored with
+/// the weight the Learner estimated for values unseen by the complement of h: it is never
+/// penalised, which is why only `UnseenPolicy::Skip` and `UnseenPolicy::Error` apply to it.
+#[derive(Clone, Debug)]
+pub struct ComplementClassifier<D, H, F = f64, S = RandomState>
+where
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    F: Float,
+    S: BuildHasher,
+{
+    // the complement weights, scored as log likelihoods with the unseen weights as the
+    // per-class estimate for unseen values
+    classifier: StreamClassifier<D, H, F, S>,
+}
+
+impl<D, H, F, S> PartialEq for ComplementClassifier<D, H, F, S>
+where
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    F: Float,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.classifier == other.classifier
+    }
+}
+
+impl<D, H, F, S> ComplementClassifier<D, H, F, S>
+where
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    F: Float,
+    S: BuildHasher + Default,
+{
+    /// Create a new Classifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `log_priors` - probability of hypothesis
+    /// * `weights` - complement weight of data given hypothesis
+    /// * `unseen_weights` - complement weight of data not seen outside hypothesis
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(ComplementClassifier)` - the classifier.
+    /// * `Err(Error::InconsistentModel)` - a weight refers to a hypothesis with no probability,
+    ///   or a hypothesis has no unseen weight.
+    ///
+    pub(crate) fn new(
+        log_priors: HashMap<H, F, S>,
+        weights: HashMap<D, Vec<(H, F)>, S>,
+        unseen_weights: HashMap<H, F, S>,
+    ) -> Result<Self, Error> {
+        Ok(ComplementClassifier {
+            classifier: StreamClassifier::new(log_priors, weights, unseen_weights)?
+                .with_unseen_policy(UnseenPolicy::Skip),
+        })
+    }
+
+    /// Set how data values that no hypothesis saw during training are handled: ignored with
+    /// `UnseenPolicy::Skip`, the default, or rejected with `UnseenPolicy::Error`.
+    ///
+    /// # Arguments
+    ///
+    /// * `unseen_policy` - the policy applied to values not seen by any hypothesis
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(ComplementClassifier)` - the Classifier with the policy.
+    /// * `Err(Error::UnsupportedPolicy)` - the policy is `UnseenPolicy::Penalty` or
+    ///   `UnseenPolicy::Smoothed`, which would score values that are evidence for a hypothesis
+    ///   as evidence against it.
+    ///
+    pub fn with_unseen_policy(mut self, unseen_policy: UnseenPolicy) -> Result<Self, Error> {
+        check_policy(unseen_policy)?;
+        self.classifier = self.classifier.with_unseen_policy(unseen_policy);
+        Ok(self)
+    }
+
+    /// Convert the weights of the Classifier to another floating point type, for example
+    /// `f32` to halve the memory used by the model.
+    ///
+    /// # Return Value
+    ///
+    /// * `ComplementClassifier` type
+    ///
+    pub fn cast<G: Float>(&self) -> ComplementClassifier<D, H, G, S> {
+        ComplementClassifier {
+            classifier: self.classifier.cast(),
+        }
+    }
+
+    /// Classify an unknown input.
+    ///
+    /// The priors are uniform, so the results rank hypotheses but are not calibrated
+    /// probabilities.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - a stream of data to classify for a single hypothesis.
+    ///
+    /// # Return Value
+    ///
+    /// * `Results` type
+    ///
+    /// # Panics
+    ///
+    /// * if the policy is `UnseenPolicy::Error` and a value was not seen by any hypothesis, see
+    ///   `try_classify`.
+    ///
+    pub fn classify(&self, stream: &mut dyn Iterator<Item = D>) -> Results<H, F> {
+        self.classifier.classify(stream)
+    }
+
+    /// Classify an unknown input, failing if the `UnseenPolicy` rejects a value.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - a stream of data to classify for a single hypothesis.
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(Results)` - the classification results.
+    /// * `Err(Error::UnseenValue)` - a value was not seen by any hypothesis during training.
+    ///
+    pub fn try_classify(
+        &self,
+        stream: &mut dyn Iterator<Item = D>,
+    ) -> Result<Results<H, F>, Error> {
+        self.classifier.try_classify(stream)
+    }
+
+    /// Classify an unknown input of borrowed values, for example `&str` values when the data
+    /// type is `String`, without converting each value to the data type.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - a stream of borrowed data to classify for a single hypothesis.
+    ///
+    /// # Return Value
+    ///
+    /// * `Results` type
+    ///
+    /// # Panics
+    ///
+    /// * if the policy is `UnseenPolicy::Error` and a value was not seen by any hypothesis, see
+    ///   `try_classify_borrowed`.
+    ///
+    pub fn classify_borrowed<'q, Q>(&self, stream: &mut dyn Iterator<Item = &'q Q>) -> Results<H, F>
+    where
+        D: Borrow<Q>,
+        Q: Eq + Hash + ?Sized + 'q,
+    {
+        self.classifier.classify_borrowed(stream)
+    }
+
+    /// Classify an unknown input of borrowed values, failing if the `UnseenPolicy` rejects a
+    /// value.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - a stream of borrowed data to classify for a single hypothesis.
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(Results)` - the classification results.
+    /// * `Err(Error::UnseenValue)` - a value was not seen by any hypothesis during training.
+    ///
+    pub fn try_classify_borrowed<'q, Q>(
+        &self,
+        stream: &mut dyn Iterator<Item = &'q Q>,
+    ) -> Result<Results<H, F>, Error>
+    where
+        D: Borrow<Q>,
+        Q: Eq + Hash + ?Sized + 'q,
+    {
+        self.classifier.try_classify_borrowed(stream)
+    }
+
+    /// Classify an unknown input into existing results, reusing working space, so that
+    /// classifying many inputs performs no heap allocation once the working space has grown.
+    ///
+    /// # Arguments
+    ///
+    /// * `scratch` - working space, which can be reused for any number of calls
+    /// * `stream` - a stream of data to classify for a single hypothesis.
+    /// * `results` - the results to overwrite with the classification results
+    ///
+    /// # Panics
+    ///
+    /// * if the policy is `UnseenPolicy::Error` and a value was not seen by any hypothesis, see
+    ///   `try_classify_into`.
+    ///
+    pub fn classify_into(
+        &self,
+        scratch: &mut Scratch<F>,
+        stream: &mut dyn Iterator<Item = D>,
+        results: &mut Results<H, F>,
+    ) {
+        self.classifier.classify_into(scratch, stream, results)
+    }
+
+    /// Classify an unknown input into existing results, reusing working space, failing if the
+    /// `UnseenPolicy` rejects a value.
+    ///
+    /// # Arguments
+    ///
+    /// * `scratch` - working space, which can be reused for any number of calls
+    /// * `stream` - a stream of data to classify for a single hypothesis.
+    /// * `results` - the results to overwrite with the classification results
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(())` - `results` holds the classification results.
+    /// * `Err(Error::UnseenValue)` - a value was not seen by any hypothesis during training.
+    ///   `results` is unchanged.
+    ///
+    pub fn try_classify_into(
+        &self,
+        scratch: &mut Scratch<F>,
+        stream: &mut dyn Iterator<Item = D>,
+        results: &mut Results<H, F>,
+    ) -> Result<(), Error> {
+        self.classifier.try_classify_into(scratch, stream, results)
+    }
+}
+
+// Only policies that do not replace the unseen weights can be applied to complement weights.
+fn check_policy(unseen_policy: UnseenPolicy) -> Result<(), Error> {
+    match unseen_policy {
+        UnseenPolicy::Skip | UnseenPolicy::Error => Ok(()),
+        UnseenPolicy::Penalty(_) | UnseenPolicy::Smoothed => Err(Error::UnsupportedPolicy),
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<D, H, F, S> serde::Serialize for ComplementClassifier<D, H, F, S>
+where
+    D: Clone + Eq + Hash + serde::Serialize,
+    H: Clone + Eq + Hash + serde::Serialize,
+    F: Float + serde::Serialize,
+    S: BuildHasher + Default,
+{
+    fn serialize<T: serde::Serializer>(&self, serializer: T) -> Result<T::Ok, T::Error> {
+        self.classifier.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, D, H, F, S> serde::Deserialize<'de> for ComplementClassifier<D, H, F, S>
+where
+    D: Clone + Eq + Hash + serde::Deserialize<'de>,
+    H: Clone + Eq + Hash + serde::Deserialize<'de>,
+    F: Float + serde::Deserialize<'de>,
+    S: BuildHasher + Default,
+{
+    fn deserialize<T: serde::Deserializer<'de>>(deserializer: T) -> Result<Self, T::Error> {
+        let classifier = StreamClassifier::<D, H, F, S>::deserialize(deserializer)?;
+        check_policy(classifier.unseen_policy()).map_err(serde::de::Error::custom)?;
+        Ok(ComplementClassifier { classifier })
+    }
+}
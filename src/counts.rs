@@ -0,0 +1,80 @@
+use crate::error::Error;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+
+// Counts are stored as f64 so that weighted instances can be learned, which means that
+// subtracting the same weights that were added may not give exactly zero.
+// Any count within this fraction of the count or the weight subtracted from it, whichever is
+// larger, is treated as zero, so that weights of any size can be forgotten.
+const RELATIVE_TOLERANCE: f64 = 1e-9;
+
+// Add each count from other to the corresponding count.
+pub(crate) fn add<K: Clone + Eq + Hash, S: BuildHasher>(
+    counts: &mut HashMap<K, f64, S>,
+    other: &HashMap<K, f64, S>,
+) {
+    for (k, c) in other {
+        *counts.entry(k.clone()).or_insert(0.0) += c;
+    }
+}
+
+// Check that a weight can be added to or subtracted from counts.
+pub(crate) fn check_weight(weight: f64) -> Result<(), Error> {
+    if weight.is_finite() && weight > 0.0 {
+        Ok(())
+    } else {
+        Err(Error::InvalidWeight)
+    }
+}
+
+// Check that subtracting each delta from the corresponding count would not make any
+// count negative, before any count is changed.
+pub(crate) fn check_subtract<K: Eq + Hash, S: BuildHasher>(
+    counts: &HashMap<K, f64, S>,
+    deltas: &HashMap<K, f64, S>,
+) -> Result<(), Error> {
+    for (k, delta) in deltas {
+        check_subtract_one(counts.get(k).copied().unwrap_or(0.0), *delta)?;
+    }
+    Ok(())
+}
+
+// Check that subtracting the delta from a single count would not make it negative.
+pub(crate) fn check_subtract_one(count: f64, delta: f64) -> Result<(), Error> {
+    if count - delta < -tolerance(count, delta) {
+        Err(Error::NegativeCount)
+    } else {
+        Ok(())
+    }
+}
+
+// Subtract each delta from the corresponding count, dropping any count that reaches zero
+// so that forgotten data values and hypotheses do not appear in a Classifier.
+pub(crate) fn subtract<K: Clone + Eq + Hash, S: BuildHasher>(
+    counts: &mut HashMap<K, f64, S>,
+    deltas: &HashMap<K, f64, S>,
+) {
+    for (k, delta) in deltas {
+        if let Some(count) = counts.get_mut(k) {
+            let tolerance = tolerance(*count, *delta);
+            *count -= delta;
+            if *count <= tolerance {
+                counts.remove(k);
+            }
+        }
+    }
+}
+
+// Subtract the delta from a single count, clamping it to exactly zero when it reaches zero.
+pub(crate) fn subtract_one(count: &mut f64, delta: f64) {
+    let tolerance = tolerance(*count, delta);
+    *count -= delta;
+    if *count <= tolerance {
+        *count = 0.0;
+    }
+}
+
+// The rounding error allowed when subtracting a delta from a count.
+fn tolerance(count: f64, delta: f64) -> f64 {
+    RELATIVE_TOLERANCE * count.max(delta)
+}
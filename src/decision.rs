@@ -0,0 +1,185 @@
+use crate::results::Results;
+use num_traits::Float;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// The outcome of deciding on a classification with a `DecisionPolicy`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Decision<H> {
+    /// The best hypothesis meets every threshold of the policy.
+    Accept(H),
+
+    /// The input is known well enough, but no hypothesis is probable enough, or the best
+    /// hypothesis is too close to the second best, to act on.
+    Abstain,
+
+    /// Too little of the input was seen in training for the classification to mean anything,
+    /// or there is nothing to classify into.
+    Unknown,
+}
+
+/// The thresholds a classification must meet to be accepted by `decide`.
+///
+/// The default policy accepts the best hypothesis of any results with a non-zero posterior
+/// probability. Each threshold is added with a `with_` method:
+///
+/// ```
+/// # use naive_bayes::{decide, Decision, DecisionPolicy, StreamLearner};
+/// # let mut learner = StreamLearner::default();
+/// # learner
+/// #     .update(&mut "cheap pills free offer".split(' '), "spam")
+/// #     .update(&mut "meeting notes for monday".split(' '), "ham");
+/// # let results = learner
+/// #     .make_classifier()
+/// #     .classify(&mut "free pills offer".split(' '));
+/// let policy = DecisionPolicy::new()
+///     .with_min_known(3)
+///     .with_min_posterior(0.8)
+///     .with_class_threshold("spam", 0.99)
+///     .with_min_margin(0.2);
+///
+/// assert_eq!(decide(&results, &policy), Decision::Accept("spam"));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "H: serde::Serialize, F: serde::Serialize",
+        deserialize = "H: serde::Deserialize<'de>, F: serde::Deserialize<'de>"
+    ))
+)]
+pub struct DecisionPolicy<H, F = f64>
+where
+    H: Clone + Eq + Hash,
+    F: Float,
+{
+    min_known: usize,
+    min_posterior: F,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::entries"))]
+    class_thresholds: HashMap<H, F>,
+    min_margin: F,
+}
+
+impl<H: Clone + Eq + Hash, F: Float> DecisionPolicy<H, F> {
+    /// Create a policy that accepts the best hypothesis of any results with a non-zero
+    /// posterior probability.
+    ///
+    /// # Return Value
+    ///
+    /// * `DecisionPolicy` type
+    ///
+    pub fn new() -> Self {
+        DecisionPolicy {
+            min_known: 0,
+            min_posterior: F::zero(),
+            class_thresholds: HashMap::new(),
+            min_margin: F::zero(),
+        }
+    }
+
+    /// Require a number of input values to have been seen in training, below which the
+    /// decision is `Decision::Unknown`. See `Results::known`.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_known` - the smallest number of known input values
+    ///
+    pub fn with_min_known(mut self, min_known: usize) -> Self {
+        self.min_known = min_known;
+        self
+    }
+
+    /// Require the posterior probability of the best hypothesis to be at least a threshold,
+    /// below which the decision is `Decision::Abstain`.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_posterior` - the smallest posterior probability, for hypotheses without a class
+    ///   threshold
+    ///
+    pub fn with_min_posterior(mut self, min_posterior: F) -> Self {
+        self.min_posterior = min_posterior;
+        self
+    }
+
+    /// Require the posterior probability of a hypothesis to be at least a threshold when it is
+    /// the best hypothesis, instead of the minimum posterior probability.
+    ///
+    /// # Arguments
+    ///
+    /// * `hypothesis` - the hypothesis the threshold applies to
+    /// * `threshold` - the smallest posterior probability of the hypothesis
+    ///
+    pub fn with_class_threshold(mut self, hypothesis: H, threshold: F) -> Self {
+        self.class_thresholds.insert(hypothesis, threshold);
+        self
+    }
+
+    /// Require the posterior probability of the best hypothesis to exceed that of the second
+    /// best by at least a margin, below which the decision is `Decision::Abstain`. See
+    /// `Results::margin`.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_margin` - the smallest difference in posterior probability
+    ///
+    pub fn with_min_margin(mut self, min_margin: F) -> Self {
+        self.min_margin = min_margin;
+        self
+    }
+}
+
+impl<H: Clone + Eq + Hash, F: Float> Default for DecisionPolicy<H, F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Decide whether to act on a classification, or to abstain.
+///
+/// The rules of the policy are applied in order:
+///
+/// * `Decision::Unknown` if fewer input values were known than the policy requires, or there is
+///   no best hypothesis with a non-zero posterior probability, for example when there are no
+///   results or no input was scored.
+/// * `Decision::Abstain` if the posterior probability of the best hypothesis is below its class
+///   threshold, or the minimum posterior probability if it has none.
+/// * `Decision::Abstain` if the margin between the best two hypotheses is below the minimum.
+/// * `Decision::Accept` with the best hypothesis otherwise.
+///
+/// # Arguments
+///
+/// * `results` - the results of a classification
+/// * `policy` - the thresholds the classification must meet
+///
+/// # Return Value
+///
+/// * `Decision` type
+///
+pub fn decide<H, F>(results: &Results<H, F>, policy: &DecisionPolicy<H, F>) -> Decision<H>
+where
+    H: Clone + Eq + Hash,
+    F: Float,
+{
+    if results.known() < policy.min_known {
+        return Decision::Unknown;
+    }
+    let (best, p) = match results.best() {
+        // NaN is not greater than 0.
+        Some((best, p)) if p > F::zero() => (best, p),
+        _ => return Decision::Unknown,
+    };
+
+    let threshold = policy
+        .class_thresholds
+        .get(&best)
+        .copied()
+        .unwrap_or(policy.min_posterior);
+    if p < threshold || results.margin() < policy.min_margin {
+        return Decision::Abstain;
+    }
+
+    Decision::Accept(best)
+}
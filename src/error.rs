@@ -0,0 +1,87 @@
+use std::fmt::{Display, Formatter};
+
+/// Errors returned by fallible operations in this crate.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Error {
+    /// A data value was not seen by any hypothesis during training, and the Classifier's
+    /// `UnseenPolicy` is `UnseenPolicy::Error`.
+    UnseenValue {
+        /// The position of the value in the input array or stream.
+        position: usize,
+    },
+
+    /// Forgetting data would make a count negative, because the data was not learned or was
+    /// learned with a smaller weight.
+    NegativeCount,
+
+    /// A weight is zero, negative, infinite or NaN.
+    InvalidWeight,
+
+    /// An `UnseenPolicy` cannot be applied to a Classifier, for example a penalty for the
+    /// complement weights of a `ComplementClassifier`.
+    UnsupportedPolicy,
+
+    /// A smoothing pseudo-count alpha, or a fraction of variance added by variance smoothing, is
+    /// zero, negative, infinite or NaN.
+    InvalidSmoothing,
+
+    /// A Learner has no training data, so a Classifier cannot be made from it.
+    EmptyModel,
+
+    /// A Learner has hypotheses but has counted no data, because it has only learned empty
+    /// streams, so the prior probability of each hypothesis is undefined.
+    EmptyData,
+
+    /// The counts or probabilities of a Learner or Classifier do not agree with each other, for
+    /// example a likelihood refers to a hypothesis that has no prior.
+    ///
+    /// This can only happen when a model has been deserialised from corrupt data.
+    InconsistentModel,
+
+    /// A model file ends before the model is complete.
+    Truncated,
+
+    /// A model file is not a valid model of the expected kind.
+    InvalidFormat,
+
+    /// A model file was written by a newer version of the format than this version supports.
+    UnsupportedVersion {
+        /// The format version of the model file.
+        version: u32,
+    },
+
+    /// A record does not match the schema of a mixed-type Learner or Classifier.
+    SchemaMismatch {
+        /// The position of the first field that does not match, or the schema length if the
+        /// record has the wrong number of fields.
+        position: usize,
+    },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::UnseenValue { position } => {
+                write!(f, "value at position {} was not seen in training", position)
+            }
+            Error::NegativeCount => write!(f, "forgetting data would make a count negative"),
+            Error::InvalidWeight => write!(f, "weight must be positive and finite"),
+            Error::UnsupportedPolicy => write!(f, "unseen policy is not supported"),
+            Error::InvalidSmoothing => write!(f, "smoothing must be positive and finite"),
+            Error::EmptyModel => write!(f, "model has no training data"),
+            Error::EmptyData => write!(f, "model has hypotheses but no counted data"),
+            Error::InconsistentModel => write!(f, "model counts or probabilities are inconsistent"),
+            Error::Truncated => write!(f, "model file is truncated"),
+            Error::InvalidFormat => write!(f, "model file is not a valid model"),
+            Error::UnsupportedVersion { version } => {
+                write!(f, "model file format version {} is not supported", version)
+            }
+            Error::SchemaMismatch { position } => {
+                write!(f, "record does not match schema at position {}", position)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
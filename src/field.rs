@@ -0,0 +1,54 @@
+/// The distribution of a field in a mixed-type record.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Field {
+    /// A single discrete value per record, such as a colour or country code.
+    Categorical,
+
+    /// A single continuous value per record, modelled as a normal distribution.
+    Gaussian,
+
+    /// A bag of discrete values per record, such as the words in a document.
+    Tokens,
+}
+
+/// The value of a field in a mixed-type record.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Value<'a, D> {
+    /// The value of a `Field::Categorical` field.
+    Categorical(D),
+
+    /// The value of a `Field::Gaussian` field.
+    Gaussian(f64),
+
+    /// The value of a `Field::Tokens` field.
+    Tokens(&'a [D]),
+}
+
+impl<D> Value<'_, D> {
+    /// Return the kind of field that this value belongs to.
+    pub fn field(&self) -> Field {
+        match self {
+            Value::Categorical(_) => Field::Categorical,
+            Value::Gaussian(_) => Field::Gaussian,
+            Value::Tokens(_) => Field::Tokens,
+        }
+    }
+}
+
+// Check that each value in a record has the kind declared at the same position in the schema.
+pub(crate) fn check_schema<D>(schema: &[Field], record: &[Value<D>]) -> Result<(), crate::Error> {
+    if let Some(position) = schema
+        .iter()
+        .zip(record)
+        .position(|(field, value)| *field != value.field())
+    {
+        return Err(crate::Error::SchemaMismatch { position });
+    }
+    if schema.len() != record.len() {
+        return Err(crate::Error::SchemaMismatch {
+            position: schema.len().min(record.len()),
+        });
+    }
+    Ok(())
+}
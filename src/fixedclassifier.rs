@@ -1,34 +1,125 @@
-use super::posteriors::posteriors;
+use super::error::Error;
+#[cfg(feature = "serde")]
+use super::interning::{extern_rows, extern_values};
+use super::interning::{hypothesis_table, intern_rows, intern_values};
+use super::likelihoods::LogLikelihoods;
+use super::modelfile::{decode, encode, ModelKeyOwned, KIND_FIXED};
+use super::precision::{cast_rows, cast_values};
 use super::results::Results;
-use std::collections::{HashMap, HashSet};
-use std::hash::Hash;
+use super::scratch::Scratch;
+use super::unseen::UnseenPolicy;
+use num_traits::Float;
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct FixedClassifier<D, H, const DS: usize>
+#[derive(Clone, Debug)]
+pub struct FixedClassifier<D, H, const DS: usize, F = f64, S = RandomState>
 where
-    D: Copy + Eq + Hash,
-    H: Copy + Eq + Hash,
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    F: Float,
+    S: BuildHasher,
 {
-    log_priors: HashMap<H, f64>,
-    // probability P is: P(D|H) * P(H)
-    log_likelihoods: [HashMap<D, Vec<(H, f64)>>; DS],
+    // each hypothesis, indexed by its id (see interning.rs)
+    hypotheses: Vec<H>,
+    // probability of each hypothesis, by id
+    log_priors: Vec<F>,
+    // probability P is: P(D|H) * P(H), with each H as an id
+    log_likelihoods: [LogLikelihoods<D, u32, F, S>; DS],
+    // probability P(D|H) assumed for values not seen with H during training, by id
+    log_unseen: [Vec<F>; DS],
+    unseen_policy: UnseenPolicy,
 }
 
-impl<D: Copy + Eq + Hash, H: Copy + Eq + Hash, const DS: usize> FixedClassifier<D, H, DS> {
+impl<D, H, const DS: usize, F, S> PartialEq for FixedClassifier<D, H, DS, F, S>
+where
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    F: Float,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.hypotheses == other.hypotheses
+            && self.log_priors == other.log_priors
+            && self.log_likelihoods == other.log_likelihoods
+            && self.log_unseen == other.log_unseen
+            && self.unseen_policy == other.unseen_policy
+    }
+}
+
+impl<D, H, const DS: usize, F, S> FixedClassifier<D, H, DS, F, S>
+where
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    F: Float,
+    S: BuildHasher + Default,
+{
     /// Create a new Classifier.
     ///
     /// # Arguments
     ///
     /// * `log_priors` - probability of hypothesis
     /// * `log_likelihoods` - probability of data given hypothesis, for each input array position
+    /// * `log_unseen` - probability of unseen data given hypothesis, for each input array position
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(FixedClassifier)` - the classifier.
+    /// * `Err(Error::InconsistentModel)` - a likelihood refers to a hypothesis with no
+    ///   probability, or a hypothesis has no probability for unseen data at some position.
     ///
     pub(crate) fn new(
-        log_priors: HashMap<H, f64>,
-        log_likelihoods: [HashMap<D, Vec<(H, f64)>>; DS],
-    ) -> Self {
+        log_priors: HashMap<H, F, S>,
+        log_likelihoods: [LogLikelihoods<D, H, F, S>; DS],
+        log_unseen: [HashMap<H, F, S>; DS],
+    ) -> Result<Self, Error> {
+        let (hypotheses, ids) = hypothesis_table(&log_priors);
+        let (log_likelihoods, log_unseen): (Vec<_>, Vec<_>) = log_likelihoods
+            .into_iter()
+            .zip(log_unseen.iter())
+            .map(|(log_likelihoods, log_unseen)| {
+                intern_rows(log_likelihoods, &ids).zip(intern_values(log_unseen, &hypotheses))
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or(Error::InconsistentModel)?
+            .into_iter()
+            .unzip();
+        Ok(FixedClassifier {
+            log_priors: intern_values(&log_priors, &hypotheses).unwrap(),
+            hypotheses,
+            log_likelihoods: to_array(log_likelihoods),
+            log_unseen: to_array(log_unseen),
+            unseen_policy: UnseenPolicy::default(),
+        })
+    }
+
+    /// Set how data values that a hypothesis did not see during training are scored.
+    ///
+    /// # Arguments
+    ///
+    /// * `unseen_policy` - the policy applied to unseen values
+    ///
+    pub fn with_unseen_policy(mut self, unseen_policy: UnseenPolicy) -> Self {
+        self.unseen_policy = unseen_policy;
+        self
+    }
+
+    /// Convert the probabilities of the Classifier to another floating point type, for example
+    /// `f32` to halve the memory used by the model.
+    ///
+    /// # Return Value
+    ///
+    /// * `FixedClassifier` type
+    ///
+    pub fn cast<G: Float>(&self) -> FixedClassifier<D, H, DS, G, S> {
         FixedClassifier {
-            log_priors,
-            log_likelihoods,
+            hypotheses: self.hypotheses.clone(),
+            log_priors: cast_values(&self.log_priors),
+            log_likelihoods: to_array(self.log_likelihoods.iter().map(cast_rows).collect()),
+            log_unseen: to_array(self.log_unseen.iter().map(|v| cast_values(v)).collect()),
+            unseen_policy: self.unseen_policy,
         }
     }
 
@@ -42,25 +133,292 @@ impl<D: Copy + Eq + Hash, H: Copy + Eq + Hash, const DS: usize> FixedClassifier<
     ///
     /// * `Results` type
     ///
-    pub fn classify(&self, data: &[D; DS]) -> Results<H> {
-        const LOG2_PLACEHOLDER_PROBABILITY: f64 = -(f64::MANTISSA_DIGITS as f64);
+    /// # Panics
+    ///
+    /// * if the policy is `UnseenPolicy::Error` and a value was not seen by any hypothesis, see
+    ///   `try_classify`.
+    ///
+    pub fn classify(&self, data: &[D; DS]) -> Results<H, F> {
+        self.try_classify(data).unwrap()
+    }
+
+    /// Classify an unknown input, failing if the `UnseenPolicy` rejects a value.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - array of input data to classify
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(Results)` - the classification results.
+    /// * `Err(Error::UnseenValue)` - a value was not seen by any hypothesis during training.
+    ///
+    pub fn try_classify(&self, data: &[D; DS]) -> Result<Results<H, F>, Error> {
+        let mut results = Results::default();
+        self.score::<D, D>(&mut Scratch::new(), data, &mut results)?;
+        Ok(results)
+    }
+
+    /// Classify an unknown input of borrowed values, for example `&str` values when the data
+    /// type is `String`, without converting each value to the data type.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - array of borrowed input data to classify
+    ///
+    /// # Return Value
+    ///
+    /// * `Results` type
+    ///
+    /// # Panics
+    ///
+    /// * if the policy is `UnseenPolicy::Error` and a value was not seen by any hypothesis, see
+    ///   `try_classify_borrowed`.
+    ///
+    pub fn classify_borrowed<Q>(&self, data: &[&Q; DS]) -> Results<H, F>
+    where
+        D: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.try_classify_borrowed(data).unwrap()
+    }
+
+    /// Classify an unknown input of borrowed values, failing if the `UnseenPolicy` rejects a
+    /// value.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - array of borrowed input data to classify
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(Results)` - the classification results.
+    /// * `Err(Error::UnseenValue)` - a value was not seen by any hypothesis during training.
+    ///
+    pub fn try_classify_borrowed<Q>(&self, data: &[&Q; DS]) -> Result<Results<H, F>, Error>
+    where
+        D: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let mut results = Results::default();
+        self.score::<Q, &Q>(&mut Scratch::new(), data, &mut results)?;
+        Ok(results)
+    }
+
+    /// Classify an unknown input into existing results, reusing working space, so that
+    /// classifying many inputs performs no heap allocation once the working space has grown.
+    ///
+    /// # Arguments
+    ///
+    /// * `scratch` - working space, which can be reused for any number of calls
+    /// * `data` - array of input data to classify
+    /// * `results` - the results to overwrite with the classification results
+    ///
+    /// # Panics
+    ///
+    /// * if the policy is `UnseenPolicy::Error` and a value was not seen by any hypothesis, see
+    ///   `try_classify_into`.
+    ///
+    pub fn classify_into(
+        &self,
+        scratch: &mut Scratch<F>,
+        data: &[D; DS],
+        results: &mut Results<H, F>,
+    ) {
+        self.try_classify_into(scratch, data, results).unwrap()
+    }
+
+    /// Classify an unknown input into existing results, reusing working space, failing if the
+    /// `UnseenPolicy` rejects a value.
+    ///
+    /// # Arguments
+    ///
+    /// * `scratch` - working space, which can be reused for any number of calls
+    /// * `data` - array of input data to classify
+    /// * `results` - the results to overwrite with the classification results
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(())` - `results` holds the classification results.
+    /// * `Err(Error::UnseenValue)` - a value was not seen by any hypothesis during training.
+    ///   `results` is unchanged.
+    ///
+    pub fn try_classify_into(
+        &self,
+        scratch: &mut Scratch<F>,
+        data: &[D; DS],
+        results: &mut Results<H, F>,
+    ) -> Result<(), Error> {
+        self.score::<D, D>(scratch, data, results)
+    }
 
-        // Accumulate product of likelihoods, grouped by hypothesis.
-        let mut log_likelihoods: HashMap<H, f64> = HashMap::default();
-        let placeholder = Vec::new();
-        let all: HashSet<&H> = self.log_priors.keys().collect();
+    // Classify an array of values that can be borrowed in the same form as the data type.
+    fn score<Q, T>(
+        &self,
+        scratch: &mut Scratch<F>,
+        data: &[T; DS],
+        results: &mut Results<H, F>,
+    ) -> Result<(), Error>
+    where
+        D: Borrow<Q>,
+        T: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        // Accumulate product of likelihoods, by hypothesis id.
+        scratch.reset(self.hypotheses.len());
         for (i, d) in data.iter().enumerate() {
-            let mut missing = all.clone();
-            let found = self.log_likelihoods[i].get(d).unwrap_or(&placeholder);
-            for (h, p) in found {
-                *log_likelihoods.entry(*h).or_insert(0.0) += p;
-                missing.remove(h);
-            }
-            for h in missing {
-                *log_likelihoods.entry(*h).or_insert(0.0) += LOG2_PLACEHOLDER_PROBABILITY;
-            }
+            let found: &[(u32, F)] = match self.log_likelihoods[i].get(d.borrow()) {
+                Some(found) => found,
+                None => match self.unseen_policy {
+                    UnseenPolicy::Skip => continue,
+                    UnseenPolicy::Error => return Err(Error::UnseenValue { position: i }),
+                    _ => &[],
+                },
+            };
+            scratch.add(
+                i,
+                found.iter().copied(),
+                &self.log_unseen[i],
+                self.unseen_policy,
+                |p| p,
+            );
         }
 
-        posteriors::<D, H>(&self.log_priors, &log_likelihoods)
+        scratch.posteriors_into(&self.hypotheses, &self.log_priors, results);
+        Ok(())
+    }
+}
+
+impl<D, H, const DS: usize, F, S> FixedClassifier<D, H, DS, F, S>
+where
+    D: Clone + Eq + Hash + ModelKeyOwned,
+    H: Clone + Eq + Hash + ModelKeyOwned,
+    F: Float,
+    S: BuildHasher + Default,
+{
+    /// Encode the Classifier in the binary model file format.
+    ///
+    /// # Return Value
+    ///
+    /// * `Vec<u8>` - the encoded model, which can be written to a file.
+    ///
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode(
+            KIND_FIXED,
+            &self.hypotheses,
+            &self.log_priors,
+            &self.log_likelihoods,
+            &self.log_unseen,
+            self.unseen_policy,
+        )
+    }
+
+    /// Decode a Classifier from the binary model file format.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - the encoded model, as returned by `to_bytes`
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(FixedClassifier)` - the decoded model.
+    /// * `Err(Error::Truncated)` - `bytes` ends before the model is complete.
+    /// * `Err(Error::InvalidFormat)` - `bytes` is not a valid `FixedClassifier` model with `DS`
+    ///   input array positions.
+    /// * `Err(Error::UnsupportedVersion)` - `bytes` was written by a newer format version.
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let model = decode(bytes, KIND_FIXED)?;
+        Ok(FixedClassifier {
+            hypotheses: model.hypotheses,
+            log_priors: model.log_priors,
+            log_likelihoods: model
+                .log_likelihoods
+                .try_into()
+                .map_err(|_| Error::InvalidFormat)?,
+            log_unseen: model
+                .log_unseen
+                .try_into()
+                .map_err(|_| Error::InvalidFormat)?,
+            unseen_policy: model.unseen_policy,
+        })
+    }
+}
+
+// Convert a Vec of DS values to an array.
+fn to_array<T, const DS: usize>(values: Vec<T>) -> [T; DS] {
+    // There is one value per position, so conversion cannot fail.
+    values.try_into().ok().unwrap()
+}
+
+// The serialized form of a FixedClassifier, which refers to hypotheses by value.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(bound(
+    serialize = "D: serde::Serialize, H: serde::Serialize, F: serde::Serialize",
+    deserialize = "D: serde::Deserialize<'de>, H: serde::Deserialize<'de>, \
+                   F: serde::Deserialize<'de>, S: BuildHasher + Default"
+))]
+struct FixedClassifierEntries<D, H, const DS: usize, F, S>
+where
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    S: BuildHasher,
+{
+    #[serde(with = "crate::serialization::entries")]
+    log_priors: HashMap<H, F, S>,
+    #[serde(with = "crate::serialization::array_entries")]
+    log_likelihoods: [LogLikelihoods<D, H, F, S>; DS],
+    #[serde(with = "crate::serialization::array_entries")]
+    log_unseen: [HashMap<H, F, S>; DS],
+    unseen_policy: UnseenPolicy,
+}
+
+#[cfg(feature = "serde")]
+impl<D, H, const DS: usize, F, S> serde::Serialize for FixedClassifier<D, H, DS, F, S>
+where
+    D: Clone + Eq + Hash + serde::Serialize,
+    H: Clone + Eq + Hash + serde::Serialize,
+    F: Float + serde::Serialize,
+    S: BuildHasher + Default,
+{
+    fn serialize<T: serde::Serializer>(&self, serializer: T) -> Result<T::Ok, T::Error> {
+        FixedClassifierEntries::<D, H, DS, F, S> {
+            log_priors: extern_values(&self.log_priors, &self.hypotheses),
+            log_likelihoods: to_array(
+                self.log_likelihoods
+                    .iter()
+                    .map(|log_likelihoods| extern_rows(log_likelihoods, &self.hypotheses))
+                    .collect(),
+            ),
+            log_unseen: to_array(
+                self.log_unseen
+                    .iter()
+                    .map(|log_unseen| extern_values(log_unseen, &self.hypotheses))
+                    .collect(),
+            ),
+            unseen_policy: self.unseen_policy,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, D, H, const DS: usize, F, S> serde::Deserialize<'de> for FixedClassifier<D, H, DS, F, S>
+where
+    D: Clone + Eq + Hash + serde::Deserialize<'de>,
+    H: Clone + Eq + Hash + serde::Deserialize<'de>,
+    F: Float + serde::Deserialize<'de>,
+    S: BuildHasher + Default,
+{
+    fn deserialize<T: serde::Deserializer<'de>>(deserializer: T) -> Result<Self, T::Error> {
+        let entries = FixedClassifierEntries::<D, H, DS, F, S>::deserialize(deserializer)?;
+        let classifier = FixedClassifier::new(
+            entries.log_priors,
+            entries.log_likelihoods,
+            entries.log_unseen,
+        )
+        .map_err(serde::de::Error::custom)?;
+        Ok(classifier.with_unseen_policy(entries.unseen_policy))
     }
 }
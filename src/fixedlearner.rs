@@ -1,37 +1,85 @@
+use crate::counts::{
+    add, check_subtract, check_subtract_one, check_weight, subtract, subtract_one,
+};
+use crate::error::Error;
 use crate::fixedclassifier::FixedClassifier;
-use crate::likelihoods::likelihoods;
+use crate::likelihoods::{likelihoods, priors, unseen_likelihoods, LogLikelihoods};
+use crate::smoothing::Smoothing;
+use std::collections::hash_map::RandomState;
 use std::collections::HashMap;
 use std::fmt::Debug;
-use std::hash::Hash;
+use std::hash::{BuildHasher, Hash};
 use std::iter::Iterator;
-use std::prelude::rust_2021::TryInto;
+use std::iter::Sum;
+use std::ops::Add;
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct FixedLearner<D, H, const DS: usize>
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "D: serde::Serialize, H: serde::Serialize",
+        deserialize = "D: serde::Deserialize<'de>, H: serde::Deserialize<'de>, \
+                       S: BuildHasher + Default"
+    ))
+)]
+pub struct FixedLearner<D, H, const DS: usize, S = RandomState>
 where
-    D: Copy + Debug + Eq + Hash,
-    H: Copy + Debug + Eq + Hash,
+    D: Clone + Debug + Eq + Hash,
+    H: Clone + Debug + Eq + Hash,
+    S: BuildHasher,
 {
-    count_hypotheses: HashMap<H, f64>,
-    count_joint: [HashMap<(D, H), f64>; DS],
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::counts"))]
+    count_hypotheses: HashMap<H, f64, S>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::array_counts"))]
+    count_joint: [HashMap<(D, H), f64, S>; DS],
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::count"))]
     count_total: f64,
 }
 
-impl<D: Copy + Debug + Eq + Hash, H: Copy + Debug + Eq + Hash, const DS: usize> Default
+impl<D: Clone + Debug + Eq + Hash, H: Clone + Debug + Eq + Hash, const DS: usize> Default
     for FixedLearner<D, H, DS>
 {
     fn default() -> Self {
+        Self::with_default_hasher()
+    }
+}
+
+impl<D, H, const DS: usize, S> PartialEq for FixedLearner<D, H, DS, S>
+where
+    D: Clone + Debug + Eq + Hash,
+    H: Clone + Debug + Eq + Hash,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.count_hypotheses == other.count_hypotheses
+            && self.count_joint == other.count_joint
+            && self.count_total == other.count_total
+    }
+}
+
+impl<D, H, const DS: usize, S> FixedLearner<D, H, DS, S>
+where
+    D: Clone + Debug + Eq + Hash,
+    H: Clone + Debug + Eq + Hash,
+    S: BuildHasher + Default,
+{
+    /// Create a Learner whose maps use the hasher type `S`, for example `FxBuildHasher`.
+    ///
+    /// Unlike `HashMap::with_hasher`, this takes no hasher instance: every map, including the
+    /// maps of the Classifiers made by the Learner, is created with `S::default()`, so the
+    /// hasher is chosen by its type and cannot be seeded per instance.
+    ///
+    /// `default()` creates a Learner that uses the standard library's hasher.
+    ///
+    pub fn with_default_hasher() -> Self {
         FixedLearner {
             count_hypotheses: HashMap::default(),
-            count_joint: [(); DS].map(|_| HashMap::<(D, H), f64>::default()),
+            count_joint: [(); DS].map(|_| HashMap::<(D, H), f64, S>::default()),
             count_total: 0.0,
         }
     }
-}
 
-impl<D: Copy + Debug + Eq + Hash, H: Copy + Debug + Eq + Hash, const DS: usize>
-    FixedLearner<D, H, DS>
-{
     /// Update the Learner with a single instance of training data for a single hypothesis.
     ///
     /// # Arguments
@@ -40,12 +88,58 @@ impl<D: Copy + Debug + Eq + Hash, H: Copy + Debug + Eq + Hash, const DS: usize>
     /// * `hypothesis` - the target hypothesis/label/category/classification for the data.
     ///
     pub fn update(&mut self, data: &[D; DS], hypothesis: H) -> &mut Self {
+        self.update_weighted(data, hypothesis, 1.0)
+    }
+
+    /// Update the Learner with a single weighted instance of training data for a single
+    /// hypothesis.
+    ///
+    /// The instance is counted as if it had occurred `weight` times.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - an array representing a single instance of training data
+    /// * `hypothesis` - the target hypothesis/label/category/classification for the data.
+    /// * `weight` - the importance of the instance, which must be positive and finite
+    ///
+    /// # Panics
+    ///
+    /// * if the weight is zero, negative, infinite or NaN, see `try_update_weighted`.
+    ///
+    pub fn update_weighted(&mut self, data: &[D; DS], hypothesis: H, weight: f64) -> &mut Self {
+        self.try_update_weighted(data, hypothesis, weight).unwrap()
+    }
+
+    /// Update the Learner with a single weighted instance of training data for a single
+    /// hypothesis, failing if the weight is invalid.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - an array representing a single instance of training data
+    /// * `hypothesis` - the target hypothesis/label/category/classification for the data.
+    /// * `weight` - the importance of the instance, which must be positive and finite
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(&mut Self)` - the instance was learned.
+    /// * `Err(Error::InvalidWeight)` - the weight is zero, negative, infinite or NaN, and the
+    ///   Learner is unchanged.
+    ///
+    pub fn try_update_weighted(
+        &mut self,
+        data: &[D; DS],
+        hypothesis: H,
+        weight: f64,
+    ) -> Result<&mut Self, Error> {
+        check_weight(weight)?;
         for (i, d) in data.iter().enumerate() {
-            *self.count_joint[i].entry((*d, hypothesis)).or_insert(0.0) += 1.0;
+            *self.count_joint[i]
+                .entry((d.clone(), hypothesis.clone()))
+                .or_insert(0.0) += weight;
         }
-        *self.count_hypotheses.entry(hypothesis).or_insert(0.0) += 1.0;
-        self.count_total += 1.0;
-        self
+        *self.count_hypotheses.entry(hypothesis).or_insert(0.0) += weight;
+        self.count_total += weight;
+        Ok(self)
     }
 
     /// Update the Learner with multiple instances of training data for a single hypothesis.
@@ -60,22 +154,123 @@ impl<D: Copy + Debug + Eq + Hash, H: Copy + Debug + Eq + Hash, const DS: usize>
         data: &mut dyn Iterator<Item = &[D; DS]>,
         hypothesis: H,
     ) -> &mut Self {
+        self.update_batch_weighted(data, hypothesis, 1.0)
+    }
+
+    /// Update the Learner with multiple instances of training data for a single hypothesis,
+    /// with the same weight applied to each instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - an Iterator over arrays that each represent a single instance of training data
+    /// * `hypothesis` - the single target hypothesis/label/category/classification for the data
+    /// * `weight` - the importance of each instance, which must be positive and finite
+    ///
+    /// # Panics
+    ///
+    /// * if the weight is zero, negative, infinite or NaN, see `try_update_batch_weighted`.
+    ///
+    pub fn update_batch_weighted(
+        &mut self,
+        data: &mut dyn Iterator<Item = &[D; DS]>,
+        hypothesis: H,
+        weight: f64,
+    ) -> &mut Self {
+        self.try_update_batch_weighted(data, hypothesis, weight)
+            .unwrap()
+    }
+
+    /// Update the Learner with multiple instances of training data for a single hypothesis,
+    /// with the same weight applied to each instance, failing if the weight is invalid.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - an Iterator over arrays that each represent a single instance of training data
+    /// * `hypothesis` - the single target hypothesis/label/category/classification for the data
+    /// * `weight` - the importance of each instance, which must be positive and finite
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(&mut Self)` - the instances were learned.
+    /// * `Err(Error::InvalidWeight)` - the weight is zero, negative, infinite or NaN, and the
+    ///   Learner is unchanged.
+    ///
+    pub fn try_update_batch_weighted(
+        &mut self,
+        data: &mut dyn Iterator<Item = &[D; DS]>,
+        hypothesis: H,
+        weight: f64,
+    ) -> Result<&mut Self, Error> {
+        check_weight(weight)?;
         let mut count = 0.0;
         for item in data {
             for (i, d) in item.iter().enumerate() {
-                *self.count_joint[i].entry((*d, hypothesis)).or_insert(0.0) += 1.0;
+                *self.count_joint[i]
+                    .entry((d.clone(), hypothesis.clone()))
+                    .or_insert(0.0) += weight;
             }
             // Retrieving the length can be expensive, for example when the data is being streamed.
             // This counter is cheap to maintain and can be processed without additional latency.
-            count += 1.0;
+            count += weight;
         }
         // Do this lookup once instead of for each item in data.
         *self.count_hypotheses.entry(hypothesis).or_insert(0.0) += count;
         self.count_total += count;
-        self
+        Ok(self)
     }
 
-    /// Make a classifier based on a snapshot of the current Learner's training.
+    /// Remove a single previously learned instance of training data for a single hypothesis.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - an array representing a single instance of training data
+    /// * `hypothesis` - the target hypothesis/label/category/classification for the data.
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(&mut Self)` - the instance was removed.
+    /// * `Err(Error::NegativeCount)` - the instance was not learned, and the Learner is unchanged.
+    ///
+    pub fn forget(&mut self, data: &[D; DS], hypothesis: H) -> Result<&mut Self, Error> {
+        self.forget_weighted(data, hypothesis, 1.0)
+    }
+
+    /// Remove a single previously learned weighted instance of training data for a single
+    /// hypothesis.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - an array representing a single instance of training data
+    /// * `hypothesis` - the target hypothesis/label/category/classification for the data.
+    /// * `weight` - the weight that the instance was learned with
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(&mut Self)` - the instance was removed.
+    /// * `Err(Error::NegativeCount)` - the instance was not learned with at least this weight, and
+    ///   the Learner is unchanged.
+    /// * `Err(Error::InvalidWeight)` - the weight is zero, negative, infinite or NaN, and the
+    ///   Learner is unchanged.
+    ///
+    pub fn forget_weighted(
+        &mut self,
+        data: &[D; DS],
+        hypothesis: H,
+        weight: f64,
+    ) -> Result<&mut Self, Error> {
+        check_weight(weight)?;
+        let deltas: Vec<HashMap<(D, H), f64, S>> = data
+            .iter()
+            .map(|d| {
+                [((d.clone(), hypothesis.clone()), weight)]
+                    .into_iter()
+                    .collect()
+            })
+            .collect();
+        self.forget_counts(&deltas, hypothesis, weight)
+    }
+
+    /// Remove multiple previously learned instances of training data for a single hypothesis.
     ///
     /// # Arguments
     ///
@@ -84,16 +279,152 @@ impl<D: Copy + Debug + Eq + Hash, H: Copy + Debug + Eq + Hash, const DS: usize>
     ///
     /// # Return Value
     ///
+    /// * `Ok(&mut Self)` - the instances were removed.
+    /// * `Err(Error::NegativeCount)` - the instances were not all learned, and the Learner is
+    ///   unchanged.
+    ///
+    pub fn forget_batch(
+        &mut self,
+        data: &mut dyn Iterator<Item = &[D; DS]>,
+        hypothesis: H,
+    ) -> Result<&mut Self, Error> {
+        let mut deltas: Vec<HashMap<(D, H), f64, S>> =
+            (0..DS).map(|_| HashMap::default()).collect();
+        let mut count = 0.0;
+        for item in data {
+            for (i, d) in item.iter().enumerate() {
+                *deltas[i]
+                    .entry((d.clone(), hypothesis.clone()))
+                    .or_insert(0.0) += 1.0;
+            }
+            count += 1.0;
+        }
+        self.forget_counts(&deltas, hypothesis, count)
+    }
+
+    // Subtract counts for each input array position and for the hypothesis, only if none of
+    // the counts would become negative.
+    fn forget_counts(
+        &mut self,
+        deltas: &[HashMap<(D, H), f64, S>],
+        hypothesis: H,
+        count: f64,
+    ) -> Result<&mut Self, Error> {
+        for (count_joint, delta) in self.count_joint.iter().zip(deltas) {
+            check_subtract(count_joint, delta)?;
+        }
+        let delta_hypotheses: HashMap<H, f64, S> = [(hypothesis, count)].into_iter().collect();
+        check_subtract(&self.count_hypotheses, &delta_hypotheses)?;
+        check_subtract_one(self.count_total, count)?;
+
+        for (count_joint, delta) in self.count_joint.iter_mut().zip(deltas) {
+            subtract(count_joint, delta);
+        }
+        subtract(&mut self.count_hypotheses, &delta_hypotheses);
+        subtract_one(&mut self.count_total, count);
+        Ok(self)
+    }
+
+    /// Add the training of another Learner to this Learner.
+    ///
+    /// The result is the same as if this Learner had also been updated with all of the other
+    /// Learner's training data, so Learners can be trained separately on disjoint shards of data
+    /// and then combined.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - the Learner to merge into this Learner
+    ///
+    pub fn merge(&mut self, other: &Self) -> &mut Self {
+        for (count_joint, other_joint) in self.count_joint.iter_mut().zip(&other.count_joint) {
+            add(count_joint, other_joint);
+        }
+        add(&mut self.count_hypotheses, &other.count_hypotheses);
+        self.count_total += other.count_total;
+        self
+    }
+
+    /// Make a classifier based on a snapshot of the current Learner's training.
+    ///
+    /// No smoothing is applied, see `make_classifier_with_smoothing`.
+    ///
+    /// # Return Value
+    ///
     /// * `Classifier` type
     ///
-    pub fn make_classifier(&mut self) -> FixedClassifier<D, H, DS> {
-        let log_priors: HashMap<H, f64> = self
-            .count_hypotheses
-            .iter()
-            .map(|(h, c)| (*h, (*c / self.count_total).log2()))
-            .collect();
+    pub fn make_classifier(&mut self) -> FixedClassifier<D, H, DS, f64, S> {
+        self.make_classifier_with_smoothing(Smoothing::None)
+    }
+
+    /// Make a classifier based on a snapshot of the current Learner's training, estimating
+    /// likelihoods with the given smoothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `smoothing` - the smoothing applied to the likelihood at each input array position
+    ///
+    /// # Return Value
+    ///
+    /// * `Classifier` type
+    ///
+    /// # Panics
+    ///
+    /// * if the smoothing alpha is not positive and finite, or if the Learner was deserialised
+    ///   from corrupt data, see `try_make_classifier_with_smoothing`.
+    ///
+    pub fn make_classifier_with_smoothing(
+        &mut self,
+        smoothing: Smoothing,
+    ) -> FixedClassifier<D, H, DS, f64, S> {
+        self.classifier(smoothing).unwrap()
+    }
 
-        let log_likelihoods: [HashMap<D, Vec<(H, f64)>>; DS] = self
+    /// Make a classifier based on a snapshot of the current Learner's training, failing if the
+    /// Learner has no training data or is inconsistent.
+    ///
+    /// No smoothing is applied, see `try_make_classifier_with_smoothing`.
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(FixedClassifier)` - the classifier.
+    /// * `Err(Error::EmptyModel)` - the Learner has no training data.
+    /// * `Err(Error::InconsistentModel)` - the Learner was deserialised from corrupt data.
+    ///
+    pub fn try_make_classifier(&mut self) -> Result<FixedClassifier<D, H, DS, f64, S>, Error> {
+        self.try_make_classifier_with_smoothing(Smoothing::None)
+    }
+
+    /// Make a classifier based on a snapshot of the current Learner's training, estimating
+    /// likelihoods with the given smoothing, failing if the Learner has no training data or is
+    /// inconsistent.
+    ///
+    /// # Arguments
+    ///
+    /// * `smoothing` - the smoothing applied to the likelihood at each input array position
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(FixedClassifier)` - the classifier.
+    /// * `Err(Error::EmptyModel)` - the Learner has no training data.
+    /// * `Err(Error::InvalidSmoothing)` - the smoothing alpha is not positive and finite.
+    /// * `Err(Error::InconsistentModel)` - the Learner was deserialised from corrupt data.
+    ///
+    pub fn try_make_classifier_with_smoothing(
+        &mut self,
+        smoothing: Smoothing,
+    ) -> Result<FixedClassifier<D, H, DS, f64, S>, Error> {
+        if self.count_hypotheses.is_empty() {
+            return Err(Error::EmptyModel);
+        }
+        self.classifier(smoothing)
+    }
+
+    // Make a classifier, failing if the smoothing is invalid or the counts are inconsistent.
+    fn classifier(&self, smoothing: Smoothing) -> Result<FixedClassifier<D, H, DS, f64, S>, Error> {
+        smoothing.check()?;
+        let log_priors: HashMap<H, f64, S> = priors(&self.count_hypotheses, self.count_total)?;
+
+        let log_likelihoods: [LogLikelihoods<D, H, f64, S>; DS] = self
             .count_joint
             // Process each position in the input array separately.
             //
@@ -103,11 +434,45 @@ impl<D: Copy + Debug + Eq + Hash, H: Copy + Debug + Eq + Hash, const DS: usize>
             // combination of data values in the array by multiplying together the probabilities at
             // each individual position.
             .iter()
-            .map(|dhc| likelihoods(&self.count_hypotheses, dhc))
-            .collect::<Vec<HashMap<D, Vec<(H, f64)>>>>()
+            .map(|dhc| likelihoods(&self.count_hypotheses, dhc, smoothing))
+            .collect::<Result<Vec<LogLikelihoods<D, H, f64, S>>, Error>>()?
             .try_into()
-            .unwrap();
+            .map_err(|_| Error::InconsistentModel)?;
+
+        // The vocabulary can differ between positions, so unseen values are estimated separately.
+        let log_unseen: [HashMap<H, f64, S>; DS] = self
+            .count_joint
+            .iter()
+            .map(|dhc| unseen_likelihoods(&self.count_hypotheses, dhc, smoothing))
+            .collect::<Vec<HashMap<H, f64, S>>>()
+            .try_into()
+            .map_err(|_| Error::InconsistentModel)?;
+
+        FixedClassifier::new(log_priors, log_likelihoods, log_unseen)
+    }
+}
+
+impl<D, H, const DS: usize, S> Add for FixedLearner<D, H, DS, S>
+where
+    D: Clone + Debug + Eq + Hash,
+    H: Clone + Debug + Eq + Hash,
+    S: BuildHasher + Default,
+{
+    type Output = Self;
 
-        FixedClassifier::new(log_priors, log_likelihoods)
+    fn add(mut self, rhs: Self) -> Self::Output {
+        self.merge(&rhs);
+        self
+    }
+}
+
+impl<D, H, const DS: usize, S> Sum for FixedLearner<D, H, DS, S>
+where
+    D: Clone + Debug + Eq + Hash,
+    H: Clone + Debug + Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::with_default_hasher(), Add::add)
     }
 }
@@ -0,0 +1,473 @@
+use crate::error::Error;
+use crate::modelfile::{
+    decode_float, decode_header, decode_len, decode_sorted, encode_float, encode_header,
+    encode_len, encoding_order, take, ModelKey, ModelKeyOwned, KIND_FROZEN,
+};
+use crate::precision::cast;
+use crate::results::Results;
+use crate::scratch::Scratch;
+use crate::unseen::UnseenPolicy;
+use num_traits::Float;
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+use std::ops::Range;
+
+// A frozen model has the header of a model file (see modelfile.rs), followed by:
+//
+//      hypotheses          u32         |H|
+//          hypothesis      H           repeated |H| times, in order of encoding, with:
+//          log prior       f64
+//          log unseen      f64
+//      vocabulary          u32         |V|
+//      entries             u32         |E|
+//      key offsets         u32         |V| + 1: the start of each value in keys, then the end
+//      keys                            each value D, in increasing order by Ord
+//      offsets             u32         |V| + 1: the start of the entries of each value, then |E|
+//      entries                         |E| of, in increasing order of index for each value:
+//          index           u32         the index of a hypothesis in the table
+//          log             f64         log2 p(D|H)
+
+// Size of a likelihood entry: a u32 hypothesis index followed by an f64 log2 probability.
+const ENTRY_SIZE: usize = 12;
+
+/// A read-only `StreamClassifier` that classifies directly from an encoded model.
+///
+/// The model is created with `StreamClassifier::to_frozen_bytes` and laid out as flat arrays:
+/// the vocabulary is sorted so values are found by binary search, and the likelihoods of each
+/// value are stored contiguously. Creating the Classifier decodes only the hypothesis table,
+/// but reads the whole model once to check it, in time proportional to its size; nothing else
+/// is copied, so the bytes can be memory-mapped from a file and shared between processes.
+///
+/// A vocabulary of `String` values is frozen from a `StreamClassifier<String, H>` and loaded as
+/// `FrozenClassifier<&str, H>`, whose values borrow the model bytes.
+#[derive(Clone, Debug)]
+pub struct FrozenClassifier<'a, D, H, F = f64>
+where
+    D: Ord + ModelKey<'a>,
+    H: Clone + Eq + Hash + ModelKey<'a>,
+    F: Float,
+{
+    hypotheses: Vec<H>,
+    // in hypothesis table order
+    log_priors: Vec<F>,
+    // probability P(D|H) assumed for values not seen with H, in hypothesis table order
+    log_unseen: Vec<F>,
+    unseen_policy: UnseenPolicy,
+    // start of each value in keys, with a final end offset
+    key_offsets: &'a [u8],
+    // sorted vocabulary
+    keys: &'a [u8],
+    // start of the likelihoods of each value in entries, with a final end offset
+    offsets: &'a [u8],
+    // (hypothesis index, log2 probability) pairs
+    entries: &'a [u8],
+    data: PhantomData<D>,
+}
+
+impl<'a, D, H, F> FrozenClassifier<'a, D, H, F>
+where
+    D: Ord + ModelKey<'a>,
+    H: Clone + Eq + Hash + ModelKey<'a>,
+    F: Float,
+{
+    /// Create a Classifier from an encoded frozen model, without copying the likelihoods.
+    ///
+    /// The whole model is checked, so that classification cannot fail on a corrupt model.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - the encoded model, as returned by `StreamClassifier::to_frozen_bytes`
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(FrozenClassifier)` - a Classifier that borrows `bytes`.
+    /// * `Err(Error::Truncated)` - `bytes` ends before the model is complete.
+    /// * `Err(Error::InvalidFormat)` - `bytes` is not a valid frozen model.
+    /// * `Err(Error::UnsupportedVersion)` - `bytes` was written by a newer format version.
+    ///
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, Error> {
+        let mut input = bytes;
+        let (positions, unseen_policy) = decode_header(&mut input, KIND_FROZEN)?;
+        if positions != 1 {
+            return Err(Error::InvalidFormat);
+        }
+
+        let count = decode_len(&mut input)?;
+        let mut hypotheses: Vec<H> = Vec::new();
+        let mut log_priors: Vec<F> = Vec::new();
+        let mut log_unseen: Vec<F> = Vec::new();
+        let mut previous = None;
+        for _ in 0..count {
+            hypotheses.push(decode_sorted(&mut input, &mut previous)?);
+            log_priors.push(decode_float(&mut input)?);
+            log_unseen.push(decode_float(&mut input)?);
+        }
+
+        let vocabulary = decode_len(&mut input)?;
+        let length = decode_len(&mut input)?;
+        let key_offsets = take(&mut input, array_size(vocabulary + 1, 4)?)?;
+        let keys = take(
+            &mut input,
+            read_u32(&key_offsets[vocabulary * 4..]) as usize,
+        )?;
+        let offsets = take(&mut input, array_size(vocabulary + 1, 4)?)?;
+        let entries = take(&mut input, array_size(length, ENTRY_SIZE)?)?;
+        if !input.is_empty() {
+            return Err(Error::InvalidFormat);
+        }
+
+        let classifier = FrozenClassifier {
+            hypotheses,
+            log_priors,
+            log_unseen,
+            unseen_policy,
+            key_offsets,
+            keys,
+            offsets,
+            entries,
+            data: PhantomData,
+        };
+        classifier.check(vocabulary, length)?;
+        Ok(classifier)
+    }
+
+    /// Set how data values that a hypothesis did not see during training are scored.
+    ///
+    /// # Arguments
+    ///
+    /// * `unseen_policy` - the policy applied to unseen values
+    ///
+    pub fn with_unseen_policy(mut self, unseen_policy: UnseenPolicy) -> Self {
+        self.unseen_policy = unseen_policy;
+        self
+    }
+
+    /// Classify an unknown input.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - a stream of data to classify for a single hypothesis.
+    ///
+    /// # Return Value
+    ///
+    /// * `Results` type
+    ///
+    /// # Panics
+    ///
+    /// * if the policy is `UnseenPolicy::Error` and a value was not seen by any hypothesis, see
+    ///   `try_classify`.
+    ///
+    pub fn classify(&self, stream: &mut dyn Iterator<Item = D>) -> Results<H, F> {
+        self.try_classify(stream).unwrap()
+    }
+
+    /// Classify an unknown input, failing if the `UnseenPolicy` rejects a value.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - a stream of data to classify for a single hypothesis.
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(Results)` - the classification results.
+    /// * `Err(Error::UnseenValue)` - a value was not seen by any hypothesis during training.
+    ///
+    pub fn try_classify(
+        &self,
+        stream: &mut dyn Iterator<Item = D>,
+    ) -> Result<Results<H, F>, Error> {
+        let mut results = Results::default();
+        self.score::<D, D>(&mut Scratch::new(), stream, &mut results)?;
+        Ok(results)
+    }
+
+    /// Classify an unknown input of borrowed values, for example `&str` values read from a
+    /// buffer, without converting each value to the data type.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - a stream of borrowed data to classify for a single hypothesis.
+    ///
+    /// # Return Value
+    ///
+    /// * `Results` type
+    ///
+    /// # Panics
+    ///
+    /// * if the policy is `UnseenPolicy::Error` and a value was not seen by any hypothesis, see
+    ///   `try_classify_borrowed`.
+    ///
+    pub fn classify_borrowed<'q, Q>(&self, stream: &mut dyn Iterator<Item = &'q Q>) -> Results<H, F>
+    where
+        D: Borrow<Q>,
+        Q: Ord + ?Sized + 'q,
+    {
+        self.try_classify_borrowed(stream).unwrap()
+    }
+
+    /// Classify an unknown input of borrowed values, failing if the `UnseenPolicy` rejects a
+    /// value.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - a stream of borrowed data to classify for a single hypothesis.
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(Results)` - the classification results.
+    /// * `Err(Error::UnseenValue)` - a value was not seen by any hypothesis during training.
+    ///
+    pub fn try_classify_borrowed<'q, Q>(
+        &self,
+        stream: &mut dyn Iterator<Item = &'q Q>,
+    ) -> Result<Results<H, F>, Error>
+    where
+        D: Borrow<Q>,
+        Q: Ord + ?Sized + 'q,
+    {
+        let mut results = Results::default();
+        self.score::<Q, &Q>(&mut Scratch::new(), stream, &mut results)?;
+        Ok(results)
+    }
+
+    /// Classify an unknown input into existing results, reusing working space, so that
+    /// classifying many inputs performs no heap allocation once the working space has grown.
+    ///
+    /// # Arguments
+    ///
+    /// * `scratch` - working space, which can be reused for any number of calls
+    /// * `stream` - a stream of data to classify for a single hypothesis.
+    /// * `results` - the results to overwrite with the classification results
+    ///
+    /// # Panics
+    ///
+    /// * if the policy is `UnseenPolicy::Error` and a value was not seen by any hypothesis, see
+    ///   `try_classify_into`.
+    ///
+    pub fn classify_into(
+        &self,
+        scratch: &mut Scratch<F>,
+        stream: &mut dyn Iterator<Item = D>,
+        results: &mut Results<H, F>,
+    ) {
+        self.try_classify_into(scratch, stream, results).unwrap()
+    }
+
+    /// Classify an unknown input into existing results, reusing working space, failing if the
+    /// `UnseenPolicy` rejects a value.
+    ///
+    /// # Arguments
+    ///
+    /// * `scratch` - working space, which can be reused for any number of calls
+    /// * `stream` - a stream of data to classify for a single hypothesis.
+    /// * `results` - the results to overwrite with the classification results
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(())` - `results` holds the classification results.
+    /// * `Err(Error::UnseenValue)` - a value was not seen by any hypothesis during training.
+    ///   `results` is unchanged.
+    ///
+    pub fn try_classify_into(
+        &self,
+        scratch: &mut Scratch<F>,
+        stream: &mut dyn Iterator<Item = D>,
+        results: &mut Results<H, F>,
+    ) -> Result<(), Error> {
+        self.score::<D, D>(scratch, stream, results)
+    }
+
+    // Classify a stream of values that can be borrowed in the same form as the data type.
+    fn score<Q, T>(
+        &self,
+        scratch: &mut Scratch<F>,
+        stream: &mut dyn Iterator<Item = T>,
+        results: &mut Results<H, F>,
+    ) -> Result<(), Error>
+    where
+        D: Borrow<Q>,
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        // Accumulate product of likelihoods, by index in the hypothesis table.
+        scratch.reset(self.hypotheses.len());
+        for (i, d) in stream.enumerate() {
+            let found = match self.find(d.borrow()) {
+                Some(found) => found,
+                None => match self.unseen_policy {
+                    UnseenPolicy::Skip => continue,
+                    UnseenPolicy::Error => return Err(Error::UnseenValue { position: i }),
+                    _ => 0..0,
+                },
+            };
+            scratch.add(
+                i,
+                found.map(|j| self.entry(j)),
+                &self.log_unseen,
+                self.unseen_policy,
+                cast,
+            );
+        }
+
+        scratch.posteriors_into(&self.hypotheses, &self.log_priors, results);
+        Ok(())
+    }
+
+    // Check that the vocabulary is sorted, that the offsets and entries are in range and that
+    // each row refers to a hypothesis at most once.
+    fn check(&self, vocabulary: usize, length: usize) -> Result<(), Error> {
+        if self.key_offset(0) != 0 || self.key_offset(vocabulary) != self.keys.len() {
+            return Err(Error::InvalidFormat);
+        }
+        if (0..vocabulary).any(|i| self.key_offset(i) > self.key_offset(i + 1)) {
+            return Err(Error::InvalidFormat);
+        }
+        let mut previous: Option<D> = None;
+        for i in 0..vocabulary {
+            let mut input = &self.keys[self.key_offset(i)..self.key_offset(i + 1)];
+            let d = D::decode(&mut input)?;
+            if !input.is_empty() || previous.map_or(false, |p| p >= d) {
+                return Err(Error::InvalidFormat);
+            }
+            previous = Some(d);
+        }
+
+        if self.offset(0) != 0 || self.offset(vocabulary) != length {
+            return Err(Error::InvalidFormat);
+        }
+        if (0..vocabulary).any(|i| self.offset(i) > self.offset(i + 1)) {
+            return Err(Error::InvalidFormat);
+        }
+        if (0..length).any(|j| self.index(j) >= self.hypotheses.len()) {
+            return Err(Error::InvalidFormat);
+        }
+        // Each row was encoded in hypothesis index order, so a repeated index is corrupt.
+        for i in 0..vocabulary {
+            let (start, end) = (self.offset(i), self.offset(i + 1));
+            if (start + 1..end).any(|j| self.index(j) <= self.index(j - 1)) {
+                return Err(Error::InvalidFormat);
+            }
+        }
+        Ok(())
+    }
+
+    // Find the range of entries for a value, by binary search of the sorted vocabulary.
+    fn find<Q>(&self, q: &Q) -> Option<Range<usize>>
+    where
+        D: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut low = 0;
+        let mut high = self.key_offsets.len() / 4 - 1;
+        while low < high {
+            let middle = low + (high - low) / 2;
+            // Keys were checked when the Classifier was created.
+            let key = self.key(middle);
+            match key.borrow().cmp(q) {
+                std::cmp::Ordering::Less => low = middle + 1,
+                std::cmp::Ordering::Greater => high = middle,
+                std::cmp::Ordering::Equal => {
+                    return Some(self.offset(middle)..self.offset(middle + 1))
+                }
+            }
+        }
+        None
+    }
+
+    fn key_offset(&self, i: usize) -> usize {
+        read_u32(&self.key_offsets[i * 4..]) as usize
+    }
+
+    fn key(&self, i: usize) -> D {
+        D::decode(&mut &self.keys[self.key_offset(i)..self.key_offset(i + 1)]).unwrap()
+    }
+
+    fn offset(&self, i: usize) -> usize {
+        read_u32(&self.offsets[i * 4..]) as usize
+    }
+
+    fn index(&self, j: usize) -> usize {
+        read_u32(&self.entries[j * ENTRY_SIZE..]) as usize
+    }
+
+    fn entry(&self, j: usize) -> (u32, f64) {
+        let start = j * ENTRY_SIZE;
+        let bits = &self.entries[start + 4..start + ENTRY_SIZE];
+        (
+            read_u32(&self.entries[start..]),
+            f64::from_bits(u64::from_le_bytes(bits.try_into().unwrap())),
+        )
+    }
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes(bytes[..4].try_into().unwrap())
+}
+
+// Size in bytes of an array, treating a size that cannot be addressed as a truncated model.
+fn array_size(count: usize, size: usize) -> Result<usize, Error> {
+    count.checked_mul(size).ok_or(Error::Truncated)
+}
+
+// Encode the hypothesis table and likelihoods of a StreamClassifier as a frozen model.
+pub(crate) fn encode_frozen<D, H, F, S>(
+    hypotheses: &[H],
+    log_priors: &[F],
+    log_likelihoods: &HashMap<D, Vec<(u32, F)>, S>,
+    log_unseen: &[F],
+    unseen_policy: UnseenPolicy,
+) -> Vec<u8>
+where
+    D: Ord + ModelKeyOwned,
+    H: ModelKeyOwned,
+    F: Float,
+    S: BuildHasher,
+{
+    let mut out = Vec::new();
+    encode_header(KIND_FROZEN, 1, unseen_policy, &mut out);
+
+    // The table is written in order of encoding, so the index of each id in the file differs.
+    let order = encoding_order(hypotheses.iter());
+    let mut indices = vec![0; order.len()];
+    for (index, id) in order.iter().enumerate() {
+        indices[*id] = index as u32;
+    }
+    encode_len(hypotheses.len(), &mut out);
+    for id in &order {
+        hypotheses[*id].encode(&mut out);
+        encode_float(log_priors[*id], &mut out);
+        encode_float(log_unseen[*id], &mut out);
+    }
+
+    let mut values: Vec<(&D, &Vec<(u32, F)>)> = log_likelihoods.iter().collect();
+    values.sort_by(|lhs, rhs| lhs.0.cmp(rhs.0));
+    encode_len(values.len(), &mut out);
+    encode_len(values.iter().map(|(_, row)| row.len()).sum(), &mut out);
+    let mut keys = Vec::new();
+    encode_len(keys.len(), &mut out);
+    for (d, _) in &values {
+        d.encode(&mut keys);
+        encode_len(keys.len(), &mut out);
+    }
+    out.extend_from_slice(&keys);
+    let mut offset = 0;
+    encode_len(offset, &mut out);
+    for (_, row) in &values {
+        offset += row.len();
+        encode_len(offset, &mut out);
+    }
+    for (_, row) in &values {
+        let mut row: Vec<(u32, F)> = row
+            .iter()
+            .map(|(id, p)| (indices[*id as usize], *p))
+            .collect();
+        row.sort_by_key(|(index, _)| *index);
+        for (index, p) in row {
+            index.encode(&mut out);
+            encode_float(p, &mut out);
+        }
+    }
+    out
+}
@@ -0,0 +1,227 @@
+use crate::error::Error;
+#[cfg(feature = "serde")]
+use crate::interning::extern_values;
+use crate::interning::{hypothesis_table, intern_values};
+use crate::precision::{cast, cast_values};
+use crate::results::Results;
+use crate::scratch::Scratch;
+use crate::unseen::UnseenPolicy;
+use num_traits::Float;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::f64::consts::{LN_2, PI};
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+
+#[derive(Clone, Debug)]
+pub struct GaussianClassifier<H, const DS: usize, F = f64, S = RandomState>
+where
+    H: Clone + Eq + Hash,
+    F: Float,
+    S: BuildHasher,
+{
+    // each hypothesis, indexed by its id (see interning.rs)
+    hypotheses: Vec<H>,
+    // probability of each hypothesis, by id
+    log_priors: Vec<F>,
+    // mean and variance of the data given hypothesis, for each input array position, by id
+    parameters: [Vec<(F, F)>; DS],
+    hasher: PhantomData<S>,
+}
+
+impl<H, const DS: usize, F, S> PartialEq for GaussianClassifier<H, DS, F, S>
+where
+    H: Clone + Eq + Hash,
+    F: Float,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.hypotheses == other.hypotheses
+            && self.log_priors == other.log_priors
+            && self.parameters == other.parameters
+    }
+}
+
+impl<H, const DS: usize, F, S> GaussianClassifier<H, DS, F, S>
+where
+    H: Clone + Eq + Hash,
+    F: Float,
+    S: BuildHasher + Default,
+{
+    /// Create a new Classifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `log_priors` - probability of hypothesis
+    /// * `parameters` - mean and variance of data given hypothesis, for each input array position
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(GaussianClassifier)` - the classifier.
+    /// * `Err(Error::InconsistentModel)` - a hypothesis has no probability or has no parameters
+    ///   at a position, a probability or mean is not finite, or a variance is not positive and
+    ///   finite.
+    ///
+    pub(crate) fn new(
+        log_priors: HashMap<H, F, S>,
+        parameters: [HashMap<H, (F, F), S>; DS],
+    ) -> Result<Self, Error> {
+        let (hypotheses, _) = hypothesis_table(&log_priors);
+        // Every position has the parameters of exactly the hypotheses of the priors.
+        let parameters = parameters
+            .iter()
+            .map(|parameters| {
+                if parameters.len() == hypotheses.len() {
+                    intern_values(parameters, &hypotheses)
+                } else {
+                    None
+                }
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or(Error::InconsistentModel)?;
+        let log_priors = intern_values(&log_priors, &hypotheses).unwrap();
+
+        let valid = log_priors.iter().all(|p| p.is_finite())
+            && parameters.iter().flatten().all(|(mean, variance)| {
+                mean.is_finite() && variance.is_finite() && *variance > F::zero()
+            });
+        if !valid {
+            return Err(Error::InconsistentModel);
+        }
+        Ok(GaussianClassifier {
+            hypotheses,
+            log_priors,
+            // Each Vec has exactly DS elements, so conversion to an array cannot fail.
+            parameters: parameters.try_into().ok().unwrap(),
+            hasher: PhantomData,
+        })
+    }
+
+    /// Convert the probabilities and parameters of the Classifier to another floating point
+    /// type, for example `f32` to halve the memory used by the model.
+    ///
+    /// # Return Value
+    ///
+    /// * `GaussianClassifier` type
+    ///
+    pub fn cast<G: Float>(&self) -> GaussianClassifier<H, DS, G, S> {
+        GaussianClassifier {
+            hypotheses: self.hypotheses.clone(),
+            log_priors: cast_values(&self.log_priors),
+            // Each Vec has exactly DS elements, so conversion to an array cannot fail.
+            parameters: self
+                .parameters
+                .iter()
+                .map(|parameters| {
+                    parameters
+                        .iter()
+                        .map(|(mean, variance)| (cast(*mean), cast(*variance)))
+                        .collect()
+                })
+                .collect::<Vec<_>>()
+                .try_into()
+                .ok()
+                .unwrap(),
+            hasher: PhantomData,
+        }
+    }
+
+    /// Classify an unknown input.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - array of input data to classify
+    ///
+    /// # Return Value
+    ///
+    /// * `Results` type
+    ///
+    pub fn classify(&self, data: &[F; DS]) -> Results<H, F> {
+        // Accumulate product of likelihoods, by hypothesis id. Every hypothesis has parameters
+        // at every position, so none is scored as unseen.
+        let mut scratch = Scratch::new();
+        scratch.reset(self.hypotheses.len());
+        for (i, (x, parameters)) in data.iter().zip(&self.parameters).enumerate() {
+            scratch.add(
+                i,
+                (0..).zip(parameters.iter().copied()),
+                &[],
+                UnseenPolicy::default(),
+                |(mean, variance)| log2_density(*x, mean, variance),
+            );
+        }
+
+        let mut results = Results::default();
+        scratch.posteriors_into(&self.hypotheses, &self.log_priors, &mut results);
+        results
+    }
+}
+
+// The serialized form of a GaussianClassifier, which refers to hypotheses by value.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(bound(
+    serialize = "H: serde::Serialize, F: serde::Serialize",
+    deserialize = "H: serde::Deserialize<'de>, F: serde::Deserialize<'de>, \
+                   S: BuildHasher + Default"
+))]
+struct GaussianClassifierEntries<H, const DS: usize, F, S>
+where
+    H: Clone + Eq + Hash,
+    S: BuildHasher,
+{
+    #[serde(with = "crate::serialization::entries")]
+    log_priors: HashMap<H, F, S>,
+    #[serde(with = "crate::serialization::array_entries")]
+    parameters: [HashMap<H, (F, F), S>; DS],
+}
+
+#[cfg(feature = "serde")]
+impl<H, const DS: usize, F, S> serde::Serialize for GaussianClassifier<H, DS, F, S>
+where
+    H: Clone + Eq + Hash + serde::Serialize,
+    F: Float + serde::Serialize,
+    S: BuildHasher + Default,
+{
+    fn serialize<T: serde::Serializer>(&self, serializer: T) -> Result<T::Ok, T::Error> {
+        GaussianClassifierEntries::<H, DS, F, S> {
+            log_priors: extern_values(&self.log_priors, &self.hypotheses),
+            // Each Vec has exactly DS elements, so conversion to an array cannot fail.
+            parameters: self
+                .parameters
+                .iter()
+                .map(|parameters| extern_values(parameters, &self.hypotheses))
+                .collect::<Vec<_>>()
+                .try_into()
+                .ok()
+                .unwrap(),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, H, const DS: usize, F, S> serde::Deserialize<'de> for GaussianClassifier<H, DS, F, S>
+where
+    H: Clone + Eq + Hash + serde::Deserialize<'de>,
+    F: Float + serde::Deserialize<'de>,
+    S: BuildHasher + Default,
+{
+    fn deserialize<T: serde::Deserializer<'de>>(deserializer: T) -> Result<Self, T::Error> {
+        let entries = GaussianClassifierEntries::<H, DS, F, S>::deserialize(deserializer)?;
+        GaussianClassifier::new(entries.log_priors, entries.parameters)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+// log2 of the normal probability density function:
+//
+//      p(x|h) = exp(-(x - mean)^2 / (2 * variance)) / sqrt(2 * pi * variance)
+//
+// The density is not a probability, but the ratio of densities between hypotheses is
+// what matters when the posteriors are normalised.
+pub(crate) fn log2_density<F: Float>(x: F, mean: F, variance: F) -> F {
+    let two = F::one() + F::one();
+    let delta = x - mean;
+    -(two * cast(PI) * variance).log2() / two - delta * delta / (two * variance * cast(LN_2))
+}
@@ -0,0 +1,230 @@
+use crate::error::Error;
+use crate::gaussianclassifier::GaussianClassifier;
+use crate::moments::{variance_epsilon, Moments, DEFAULT_VARIANCE_SMOOTHING};
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::{BuildHasher, Hash};
+use std::iter::Iterator;
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "H: serde::Serialize",
+        deserialize = "H: serde::Deserialize<'de>, S: BuildHasher + Default"
+    ))
+)]
+pub struct GaussianLearner<H, const DS: usize, S = RandomState>
+where
+    H: Clone + Debug + Eq + Hash,
+    S: BuildHasher,
+{
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::counts"))]
+    count_hypotheses: HashMap<H, f64, S>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::array_entries"))]
+    moments: [HashMap<H, Moments, S>; DS],
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::count"))]
+    count_total: f64,
+}
+
+impl<H: Clone + Debug + Eq + Hash, const DS: usize> Default for GaussianLearner<H, DS> {
+    fn default() -> Self {
+        Self::with_default_hasher()
+    }
+}
+
+impl<H, const DS: usize, S> PartialEq for GaussianLearner<H, DS, S>
+where
+    H: Clone + Debug + Eq + Hash,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.count_hypotheses == other.count_hypotheses
+            && self.moments == other.moments
+            && self.count_total == other.count_total
+    }
+}
+
+impl<H, const DS: usize, S> GaussianLearner<H, DS, S>
+where
+    H: Clone + Debug + Eq + Hash,
+    S: BuildHasher + Default,
+{
+    /// The default variance smoothing, as a fraction of the largest variance.
+    pub const DEFAULT_VARIANCE_SMOOTHING: f64 = DEFAULT_VARIANCE_SMOOTHING;
+
+    /// Create a Learner whose maps use the hasher type `S`, for example `FxBuildHasher`.
+    ///
+    /// Unlike `HashMap::with_hasher`, this takes no hasher instance: every map, including the
+    /// maps of the Classifiers made by the Learner, is created with `S::default()`, so the
+    /// hasher is chosen by its type and cannot be seeded per instance.
+    ///
+    /// `default()` creates a Learner that uses the standard library's hasher.
+    ///
+    pub fn with_default_hasher() -> Self {
+        GaussianLearner {
+            count_hypotheses: HashMap::default(),
+            moments: [(); DS].map(|_| HashMap::<H, Moments, S>::default()),
+            count_total: 0.0,
+        }
+    }
+
+    /// Update the Learner with a single instance of training data for a single hypothesis.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - an array representing a single instance of training data
+    /// * `hypothesis` - the target hypothesis/label/category/classification for the data.
+    ///
+    pub fn update(&mut self, data: &[f64; DS], hypothesis: H) -> &mut Self {
+        for (i, x) in data.iter().enumerate() {
+            self.moments[i]
+                .entry(hypothesis.clone())
+                .or_default()
+                .update(*x);
+        }
+        *self.count_hypotheses.entry(hypothesis).or_insert(0.0) += 1.0;
+        self.count_total += 1.0;
+        self
+    }
+
+    /// Update the Learner with multiple instances of training data for a single hypothesis.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - an Iterator over arrays that each represent a single instance of training data
+    /// * `hypothesis` - the single target hypothesis/label/category/classification for the data
+    ///
+    pub fn update_batch(
+        &mut self,
+        data: &mut dyn Iterator<Item = &[f64; DS]>,
+        hypothesis: H,
+    ) -> &mut Self {
+        let mut count = 0.0;
+        for item in data {
+            for (i, x) in item.iter().enumerate() {
+                self.moments[i]
+                    .entry(hypothesis.clone())
+                    .or_default()
+                    .update(*x);
+            }
+            count += 1.0;
+        }
+        // Do this lookup once instead of for each item in data.
+        *self.count_hypotheses.entry(hypothesis).or_insert(0.0) += count;
+        self.count_total += count;
+        self
+    }
+
+    /// Make a classifier based on a snapshot of the current Learner's training.
+    ///
+    /// Uses `DEFAULT_VARIANCE_SMOOTHING`, see `make_classifier_with_variance_smoothing`.
+    ///
+    /// # Return Value
+    ///
+    /// * `GaussianClassifier` type
+    ///
+    pub fn make_classifier(&mut self) -> GaussianClassifier<H, DS, f64, S> {
+        self.make_classifier_with_variance_smoothing(Self::DEFAULT_VARIANCE_SMOOTHING)
+    }
+
+    /// Make a classifier based on a snapshot of the current Learner's training.
+    ///
+    /// A variance of zero would make the density infinite at the mean and zero elsewhere, so a
+    /// fraction of the largest variance across all positions and hypotheses is added to every
+    /// variance.
+    ///
+    /// # Arguments
+    ///
+    /// * `variance_smoothing` - fraction of the largest variance added to every variance
+    ///
+    /// # Return Value
+    ///
+    /// * `GaussianClassifier` type
+    ///
+    /// # Panics
+    ///
+    /// * if the variance smoothing is not positive and finite, or if the Learner was
+    ///   deserialised from corrupt data, see `try_make_classifier_with_variance_smoothing`.
+    ///
+    pub fn make_classifier_with_variance_smoothing(
+        &mut self,
+        variance_smoothing: f64,
+    ) -> GaussianClassifier<H, DS, f64, S> {
+        self.classifier(variance_smoothing).unwrap()
+    }
+
+    /// Make a classifier based on a snapshot of the current Learner's training, failing if the
+    /// Learner has no training data or is inconsistent.
+    ///
+    /// Uses `DEFAULT_VARIANCE_SMOOTHING`, see `try_make_classifier_with_variance_smoothing`.
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(GaussianClassifier)` - the classifier.
+    /// * `Err(Error::EmptyModel)` - the Learner has no training data.
+    /// * `Err(Error::InconsistentModel)` - the Learner was deserialised from corrupt data.
+    ///
+    pub fn try_make_classifier(&mut self) -> Result<GaussianClassifier<H, DS, f64, S>, Error> {
+        self.try_make_classifier_with_variance_smoothing(Self::DEFAULT_VARIANCE_SMOOTHING)
+    }
+
+    /// Make a classifier based on a snapshot of the current Learner's training, failing if the
+    /// Learner has no training data or is inconsistent.
+    ///
+    /// See `make_classifier_with_variance_smoothing`.
+    ///
+    /// # Arguments
+    ///
+    /// * `variance_smoothing` - fraction of the largest variance added to every variance
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(GaussianClassifier)` - the classifier.
+    /// * `Err(Error::EmptyModel)` - the Learner has no training data.
+    /// * `Err(Error::InvalidSmoothing)` - the variance smoothing is not positive and finite.
+    /// * `Err(Error::InconsistentModel)` - the Learner was deserialised from corrupt data.
+    ///
+    pub fn try_make_classifier_with_variance_smoothing(
+        &mut self,
+        variance_smoothing: f64,
+    ) -> Result<GaussianClassifier<H, DS, f64, S>, Error> {
+        if self.count_hypotheses.is_empty() {
+            return Err(Error::EmptyModel);
+        }
+        self.classifier(variance_smoothing)
+    }
+
+    // Make a classifier, failing if the smoothing is invalid or the moments are inconsistent.
+    fn classifier(
+        &self,
+        variance_smoothing: f64,
+    ) -> Result<GaussianClassifier<H, DS, f64, S>, Error> {
+        if !(variance_smoothing.is_finite() && variance_smoothing > 0.0) {
+            return Err(Error::InvalidSmoothing);
+        }
+        let log_priors: HashMap<H, f64, S> = self
+            .count_hypotheses
+            .iter()
+            .map(|(h, c)| (h.clone(), (*c / self.count_total).log2()))
+            .collect();
+
+        let epsilon = variance_epsilon(
+            self.moments.iter().flat_map(HashMap::values),
+            variance_smoothing,
+        );
+
+        let mut parameters: [HashMap<H, (f64, f64), S>; DS] =
+            [(); DS].map(|_| HashMap::<H, (f64, f64), S>::default());
+        for (parameters, hm) in parameters.iter_mut().zip(&self.moments) {
+            *parameters = hm
+                .iter()
+                .map(|(h, m)| (h.clone(), (m.mean(), m.variance() + epsilon)))
+                .collect();
+        }
+
+        GaussianClassifier::new(log_priors, parameters)
+    }
+}
@@ -0,0 +1,93 @@
+// Hashers and deterministic ordering.
+//
+// Learners and Classifiers are generic over the BuildHasher used by their maps. The standard
+// library's RandomState is the default, but it is keyed randomly for each process, so the
+// iteration order of a map differs from run to run. Anything visible outside the crate that
+// would otherwise follow that order (serialised maps, the rows of likelihoods and ties between
+// hypotheses) is instead ordered by `stable_hash`.
+//
+// `stable_hash` uses 64 bit FNV-1a, which is fully specified, rather than the standard library's
+// DefaultHasher, whose algorithm may change between Rust releases. Integers are hashed as their
+// little-endian bytes, and usize as a u64, so the order is also the same on every platform.
+//
+// RandomState does not implement PartialEq, so the Learners and Classifiers implement PartialEq
+// by comparing their fields, rather than deriving it with a bound on the hasher.
+use std::hash::{Hash, Hasher};
+
+/// A fast, non-cryptographic hasher for Learners and Classifiers, from the `rustc-hash` crate.
+///
+/// It is deterministic across runs and much faster than the default hasher for short keys such
+/// as characters, bytes and words, but offers no protection against adversarial keys that are
+/// chosen to collide.
+#[cfg(feature = "fxhash")]
+pub type FxBuildHasher = std::hash::BuildHasherDefault<rustc_hash::FxHasher>;
+
+// Hash a value with FNV-1a, so that the result is the same in every run, build and platform.
+pub(crate) fn stable_hash<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = Fnv1a(FNV_OFFSET_BASIS);
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+// The 64 bit FNV-1a hash of the bytes written so far.
+struct Fnv1a(u64);
+
+impl Hasher for Fnv1a {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ *byte as u64).wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16);
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32);
+    }
+
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128);
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.write_u64(i as u64);
+    }
+}
+
+// Sort the likelihoods of each hypothesis in a row into a deterministic order.
+pub(crate) fn sort_row<H: Hash>(row: &mut [(H, f64)]) {
+    row.sort_by_cached_key(|(h, _)| stable_hash(h));
+}
@@ -0,0 +1,107 @@
+// Dense hypothesis ids.
+//
+// Classifiers store each hypothesis once, in a table, and refer to it everywhere else by its
+// index in the table. Per-hypothesis values are stored in Vecs indexed by id, likelihood rows
+// hold ids rather than hypotheses, and classification accumulates scores in a Vec indexed by
+// id, converting back to hypotheses only when the Results are built.
+//
+// The table is sorted by the stable hash of the hypotheses, so the ids are the same in every
+// run. Serialized Classifiers and model files refer to hypotheses by value, so they are
+// converted to and from maps keyed by hypothesis.
+use crate::hashing::stable_hash;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+
+// Build the table of hypotheses from the keys of a map, and the id of each hypothesis.
+pub(crate) fn hypothesis_table<H, V, S>(map: &HashMap<H, V, S>) -> (Vec<H>, HashMap<H, u32, S>)
+where
+    H: Clone + Eq + Hash,
+    S: BuildHasher + Default,
+{
+    let mut hypotheses: Vec<H> = map.keys().cloned().collect();
+    hypotheses.sort_by_cached_key(stable_hash);
+    let ids = hypotheses
+        .iter()
+        .enumerate()
+        .map(|(id, h)| (h.clone(), id as u32))
+        .collect();
+    (hypotheses, ids)
+}
+
+// The positions of hypotheses in order of their stable hash, which is the order of their ids.
+pub(crate) fn intern_order<H: Hash>(hypotheses: &[H]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..hypotheses.len()).collect();
+    order.sort_by_cached_key(|i| stable_hash(&hypotheses[*i]));
+    order
+}
+
+// Arrange the values of a map by hypothesis id, or None if a hypothesis has no value.
+pub(crate) fn intern_values<H, V, S>(map: &HashMap<H, V, S>, hypotheses: &[H]) -> Option<Vec<V>>
+where
+    H: Eq + Hash,
+    V: Copy,
+    S: BuildHasher,
+{
+    hypotheses.iter().map(|h| map.get(h).copied()).collect()
+}
+
+// Replace the hypotheses in each row with their ids, or None if a hypothesis has no id.
+#[allow(clippy::type_complexity)]
+pub(crate) fn intern_rows<D, H, V, S>(
+    rows: HashMap<D, Vec<(H, V)>, S>,
+    ids: &HashMap<H, u32, S>,
+) -> Option<HashMap<D, Vec<(u32, V)>, S>>
+where
+    D: Eq + Hash,
+    H: Eq + Hash,
+    S: BuildHasher + Default,
+{
+    rows.into_iter()
+        .map(|(d, row)| {
+            let row = row
+                .into_iter()
+                .map(|(h, v)| Some((*ids.get(&h)?, v)))
+                .collect::<Option<Vec<_>>>()?;
+            Some((d, row))
+        })
+        .collect()
+}
+
+// Key values arranged by hypothesis id by their hypotheses.
+#[cfg(feature = "serde")]
+pub(crate) fn extern_values<H, V, S>(values: &[V], hypotheses: &[H]) -> HashMap<H, V, S>
+where
+    H: Clone + Eq + Hash,
+    V: Copy,
+    S: BuildHasher + Default,
+{
+    hypotheses
+        .iter()
+        .cloned()
+        .zip(values.iter().copied())
+        .collect()
+}
+
+// Replace the ids in each row with their hypotheses.
+#[cfg(feature = "serde")]
+#[allow(clippy::type_complexity)]
+pub(crate) fn extern_rows<D, H, V, S>(
+    rows: &HashMap<D, Vec<(u32, V)>, S>,
+    hypotheses: &[H],
+) -> HashMap<D, Vec<(H, V)>, S>
+where
+    D: Clone + Eq + Hash,
+    H: Clone,
+    V: Copy,
+    S: BuildHasher + Default,
+{
+    rows.iter()
+        .map(|(d, row)| {
+            let row = row
+                .iter()
+                .map(|(id, v)| (hypotheses[*id as usize].clone(), *v))
+                .collect();
+            (d.clone(), row)
+        })
+        .collect()
+}
@@ -1,16 +1,61 @@
 #![doc = include_str!("../README.md")]
 
+mod bernoulliclassifier;
+mod bernoullilearner;
+mod complementclassifier;
+mod decision;
+mod error;
+mod field;
 mod fixedclassifier;
 mod fixedlearner;
+mod frozenclassifier;
+mod gaussianclassifier;
+mod gaussianlearner;
+mod mixedclassifier;
+mod mixedlearner;
+mod modelfile;
+mod quantization;
+mod quantizedclassifier;
 mod results;
+mod scratch;
+mod smoothing;
 mod streamclassifier;
 mod streamlearner;
+mod unseen;
 
+mod counts;
+mod hashing;
+mod interning;
 mod likelihoods;
+mod moments;
+#[cfg(feature = "rayon")]
+mod parallel;
 mod posteriors;
+mod precision;
+#[cfg(feature = "serde")]
+mod serialization;
 
+pub use bernoulliclassifier::BernoulliClassifier;
+pub use bernoullilearner::BernoulliLearner;
+pub use complementclassifier::ComplementClassifier;
+pub use decision::{decide, Decision, DecisionPolicy};
+pub use error::Error;
+pub use field::{Field, Value};
 pub use fixedclassifier::FixedClassifier;
 pub use fixedlearner::FixedLearner;
+pub use frozenclassifier::FrozenClassifier;
+pub use gaussianclassifier::GaussianClassifier;
+pub use gaussianlearner::GaussianLearner;
+#[cfg(feature = "fxhash")]
+pub use hashing::FxBuildHasher;
+pub use mixedclassifier::MixedClassifier;
+pub use mixedlearner::MixedLearner;
+pub use modelfile::{ModelKey, ModelKeyOwned};
+pub use quantization::{Bucket, QuantizationReport};
+pub use quantizedclassifier::QuantizedClassifier;
 pub use results::Results;
+pub use scratch::Scratch;
+pub use smoothing::Smoothing;
 pub use streamclassifier::StreamClassifier;
 pub use streamlearner::StreamLearner;
+pub use unseen::UnseenPolicy;
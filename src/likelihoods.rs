@@ -1,10 +1,38 @@
-use std::collections::HashMap;
-use std::hash::Hash;
+use crate::error::Error;
+use crate::hashing::sort_row;
+use crate::smoothing::Smoothing;
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasher, Hash};
+
+// Log2 likelihood of each hypothesis, keyed by data value (see `likelihoods`).
+pub(crate) type LogLikelihoods<D, H, F = f64, S = RandomState> = HashMap<D, Vec<(H, F)>, S>;
+
+// Determine log2(p(h)) given count of |h| and the total count of all hypotheses.
+//
+// Fails with Error::EmptyData if there are hypotheses but the total is zero, as their priors
+// would all be NaN.
+pub(crate) fn priors<H: Clone + Eq + Hash, S: BuildHasher + Default>(
+    count_hypotheses: &HashMap<H, f64, S>,
+    count_total: f64,
+) -> Result<HashMap<H, f64, S>, Error> {
+    if count_total <= 0.0 && !count_hypotheses.is_empty() {
+        return Err(Error::EmptyData);
+    }
+    Ok(count_hypotheses
+        .iter()
+        .map(|(h, c)| (h.clone(), (*c / count_total).log2()))
+        .collect())
+}
 
 // Determine p(d|h) given count of |(d,h)| and count of |h|:
 //
 //      p(d|h) = |(d,h)| / |h|
 //
+// or, when additive smoothing is applied with pseudo-count alpha and vocabulary |V|:
+//
+//      p(d|h) = (|(d,h)| + alpha) / (|h| + alpha * |V|)
+//
 // This probability is conditional on h, and log2 of this value is precomputed and
 // stored for later use by the Classifier.
 //
@@ -13,7 +41,7 @@ use std::hash::Hash;
 // Multiplication by zero values should also be avoided, as this will always result
 // in a final estimate of zero.
 // For this reason, the Classifier will assume that missing values of p(d|h) have
-// some small but non-zero estimate of the probability.
+// some small but non-zero estimate of the probability (see `unseen_likelihoods`).
 //
 // To speed up classification, the mapping:
 //
@@ -25,26 +53,236 @@ use std::hash::Hash;
 //
 // This requires only O(|i|) lookups, where |i| is the number of positions in the
 // input array.
-pub(crate) fn likelihoods<D: Copy + Eq + Hash, H: Copy + Eq + Hash>(
-    count_hypotheses: &HashMap<H, f64>,
-    count_joint: &HashMap<(D, H), f64>,
-) -> HashMap<D, Vec<(H, f64)>> {
+//
+// Fails with Error::InconsistentModel if a joint count refers to a hypothesis with no count.
+pub(crate) fn likelihoods<D: Clone + Eq + Hash, H: Clone + Eq + Hash, S: BuildHasher + Default>(
+    count_hypotheses: &HashMap<H, f64, S>,
+    count_joint: &HashMap<(D, H), f64, S>,
+    smoothing: Smoothing,
+) -> Result<LogLikelihoods<D, H, f64, S>, Error> {
+    let vocabulary = vocabulary(count_joint);
+    // Each (d, h) occurs once in the joint counts, so the likelihoods of each d can be stored
+    // in a compact Vec rather than another HashMap.
+    let mut acc: HashMap<&D, Vec<(H, f64)>, S> = HashMap::default();
+    for ((d, h), c) in count_joint {
+        let count_h = count_hypotheses.get(h).ok_or(Error::InconsistentModel)?;
+        acc.entry(d).or_default().push((
+            h.clone(),
+            smoothing.log2_likelihood(*c, *count_h, vocabulary),
+        ));
+    }
+    Ok(to_owned_keys(sort_rows(acc)))
+}
+
+// Clone each key of a map keyed by reference, once the map is complete.
+fn to_owned_keys<K: Clone + Eq + Hash, V, S: BuildHasher + Default>(
+    map: HashMap<&K, V, S>,
+) -> HashMap<K, V, S> {
+    map.into_iter().map(|(k, v)| (k.clone(), v)).collect()
+}
+
+// Sort each row of likelihoods, which are built in the iteration order of the joint counts.
+fn sort_rows<D, H: Hash, S>(
+    mut map: HashMap<&D, Vec<(H, f64)>, S>,
+) -> HashMap<&D, Vec<(H, f64)>, S> {
+    for row in map.values_mut() {
+        sort_row(row);
+    }
+    map
+}
+
+// Determine the estimate of log2(p(d|h)) for values d that were never seen with h.
+//
+// Without smoothing this is a fixed placeholder. With additive smoothing it depends on
+// the count of |h| and the size of the vocabulary, so that classes with more training
+// data assign a lower probability to values they have never seen.
+pub(crate) fn unseen_likelihoods<
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    S: BuildHasher + Default,
+>(
+    count_hypotheses: &HashMap<H, f64, S>,
+    count_joint: &HashMap<(D, H), f64, S>,
+    smoothing: Smoothing,
+) -> HashMap<H, f64, S> {
+    let vocabulary = vocabulary(count_joint);
+    count_hypotheses
+        .iter()
+        .map(|(h, c)| (h.clone(), smoothing.log2_unseen(*c, vocabulary)))
+        .collect()
+}
+
+// Count the distinct data values seen during training, across all hypotheses.
+fn vocabulary<D: Clone + Eq + Hash, H: Clone + Eq + Hash, S: BuildHasher + Default>(
+    count_joint: &HashMap<(D, H), f64, S>,
+) -> usize {
     count_joint
+        .keys()
+        .map(|(d, _)| d)
+        .collect::<HashSet<_, S>>()
+        .len()
+}
+
+// Determine the Bernoulli (presence/absence) model of documents with hypothesis h.
+//
+// The probability of a document x given h considers every value d in the vocabulary V,
+// whether or not it is present in x:
+//
+//      p(x|h) = product over d in V of p(d|h)^x_d * (1 - p(d|h))^(1 - x_d)
+//
+// In log space, this is the sum of log2(1 - p(d|h)) over the whole vocabulary, plus a
+// correction for each value present in x:
+//
+//      log2(p(x|h)) = sum over d in V of log2(1 - p(d|h))
+//                   + sum over d in x of (log2(p(d|h)) - log2(1 - p(d|h)))
+//
+// The first sum does not depend on x and is precomputed per hypothesis, so classification
+// only needs lookups for the values present in x.
+// As with `likelihoods`, the correction is only stored for (d, h) seen in training; the
+// correction for unseen (d, h) depends only on the count of |h| and is stored per hypothesis.
+//
+// Returns (corrections, unseen corrections, log2 probability of absence of all of V), or
+// Error::InconsistentModel if a joint count refers to a hypothesis with no count.
+#[allow(clippy::type_complexity)]
+pub(crate) fn presence_likelihoods<
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    S: BuildHasher + Default,
+>(
+    count_hypotheses: &HashMap<H, f64, S>,
+    count_joint: &HashMap<(D, H), f64, S>,
+    smoothing: Smoothing,
+) -> Result<
+    (
+        LogLikelihoods<D, H, f64, S>,
+        HashMap<H, f64, S>,
+        HashMap<H, f64, S>,
+    ),
+    Error,
+> {
+    let vocabulary = vocabulary(count_joint) as f64;
+
+    let log_unseen: HashMap<H, (f64, f64), S> = count_hypotheses
         .iter()
-        .fold(
-            HashMap::default(),
-            |mut acc: HashMap<D, HashMap<H, f64>>, ((d, h), c)| {
-                acc.entry(*d)
-                    .or_insert_with(HashMap::default)
-                    .insert(*h, (*c / count_hypotheses.get(h).unwrap()).log2());
-                acc
-            },
-        )
-        // Reduce storage again by converting the HashMap to a more compact Vec.
+        .map(|(h, c)| (h.clone(), smoothing.log2_presence(0.0, *c)))
+        .collect();
+
+    // Start with every value in the vocabulary unseen, and replace the estimate for each
+    // value seen with h.
+    let mut log_absent: HashMap<H, f64, S> = log_unseen
         .iter()
-        .map(|(d, hp)| {
-            let v = hp.iter().map(|x| (*x.0, *x.1)).collect();
-            (*d, v)
-        })
-        .collect()
+        .map(|(h, (_, absent))| (h.clone(), vocabulary * absent))
+        .collect();
+
+    let mut log_ratios: HashMap<&D, Vec<(H, f64)>, S> = HashMap::default();
+    for ((d, h), c) in count_joint {
+        let count_h = count_hypotheses.get(h).ok_or(Error::InconsistentModel)?;
+        let (present, absent) = smoothing.log2_presence(*c, *count_h);
+        // log_absent and log_unseen have the same keys as count_hypotheses.
+        *log_absent.get_mut(h).unwrap() += absent - log_unseen[h].1;
+        log_ratios
+            .entry(d)
+            .or_default()
+            .push((h.clone(), present - absent));
+    }
+
+    let log_unseen_ratios = log_unseen
+        .iter()
+        .map(|(h, (present, absent))| (h.clone(), present - absent))
+        .collect();
+
+    Ok((
+        to_owned_keys(sort_rows(log_ratios)),
+        log_unseen_ratios,
+        log_absent,
+    ))
+}
+
+// Determine the Complement Naive Bayes weights of each value d for each hypothesis h.
+//
+// Multinomial estimates of p(d|h) are biased towards hypotheses with more training data.
+// Complement Naive Bayes instead estimates p(d|~h) from the training data of every
+// hypothesis except h, which is less affected by the imbalance:
+//
+//      p(d|~h) = |(d,~h)| / |~h|
+//
+// The value d is evidence against h in proportion to p(d|~h), so the weight is negated
+// to allow the Classifier to add it as if it were a log likelihood:
+//
+//      w(d,h) = -log2(p(d|~h))
+//
+// When normalising, each weight is divided by the sum of |w(d,h)| over the vocabulary,
+// which reduces the influence of hypotheses whose weights are larger overall.
+//
+// Returns (weights, unseen weights) in the same layout as `likelihoods` and
+// `unseen_likelihoods`.
+#[allow(clippy::type_complexity)]
+pub(crate) fn complement_likelihoods<
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    S: BuildHasher + Default,
+>(
+    count_hypotheses: &HashMap<H, f64, S>,
+    count_joint: &HashMap<(D, H), f64, S>,
+    smoothing: Smoothing,
+    normalise: bool,
+) -> (LogLikelihoods<D, H, f64, S>, HashMap<H, f64, S>) {
+    let vocabulary = vocabulary(count_joint);
+    let count_total: f64 = count_hypotheses.values().sum();
+
+    // The count of each d, and of each (d, h) grouped by d.
+    #[allow(clippy::type_complexity)]
+    let mut count_data: HashMap<&D, (f64, HashMap<&H, f64, S>), S> = HashMap::default();
+    for ((d, h), c) in count_joint {
+        let (cd, joint) = count_data.entry(d).or_default();
+        *cd += c;
+        joint.insert(h, *c);
+    }
+    let count_complement: HashMap<H, f64, S> = count_hypotheses
+        .iter()
+        .map(|(h, c)| (h.clone(), count_total - c))
+        .collect();
+
+    let mut weights: HashMap<&D, Vec<(H, f64)>, S> = HashMap::default();
+    for (d, (cd, joint)) in &count_data {
+        for (h, ch) in &count_complement {
+            let c = cd - joint.get(h).unwrap_or(&0.0);
+            if c > 0.0 {
+                weights
+                    .entry(*d)
+                    .or_default()
+                    .push((h.clone(), -smoothing.log2_likelihood(c, *ch, vocabulary)));
+            }
+        }
+    }
+    let mut unseen_weights: HashMap<H, f64, S> = count_complement
+        .iter()
+        .map(|(h, c)| (h.clone(), -smoothing.log2_unseen(*c, vocabulary)))
+        .collect();
+
+    if normalise {
+        // Values not seen with ~h have the unseen weight, so start by assuming every value
+        // in the vocabulary is unseen and correct for those that were seen.
+        let mut norms: HashMap<H, f64, S> = unseen_weights
+            .iter()
+            .map(|(h, w)| (h.clone(), vocabulary as f64 * w.abs()))
+            .collect();
+        for (h, w) in weights.values().flatten() {
+            *norms.get_mut(h).unwrap() += w.abs() - unseen_weights[h].abs();
+        }
+        // Every weight of a hypothesis with a norm of 0 is 0, for example when there is only
+        // one hypothesis and ~h is empty, so it is left unchanged rather than divided by 0.
+        for (h, w) in weights.values_mut().flatten() {
+            if norms[h] > 0.0 {
+                *w /= norms[h];
+            }
+        }
+        for (h, w) in unseen_weights.iter_mut() {
+            if norms[h] > 0.0 {
+                *w /= norms[h];
+            }
+        }
+    }
+
+    (to_owned_keys(sort_rows(weights)), unseen_weights)
 }
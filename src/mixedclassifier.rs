@@ -0,0 +1,441 @@
+use crate::error::Error;
+use crate::field::{check_schema, Field, Value};
+use crate::gaussianclassifier::log2_density;
+#[cfg(feature = "serde")]
+use crate::interning::{extern_rows, extern_values};
+use crate::interning::{hypothesis_table, intern_rows, intern_values};
+use crate::precision::{cast, cast_rows, cast_values};
+use crate::results::Results;
+use crate::scratch::Scratch;
+use crate::unseen::UnseenPolicy;
+use num_traits::Float;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+
+// The model of a field, as made by a MixedLearner and serialized, with each hypothesis by value.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "D: serde::Serialize, H: serde::Serialize, F: serde::Serialize",
+        deserialize = "D: serde::Deserialize<'de>, H: serde::Deserialize<'de>, \
+                       F: serde::Deserialize<'de>, S: BuildHasher + Default"
+    ))
+)]
+pub(crate) enum FieldModel<D, H, F, S>
+where
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    F: Float,
+    S: BuildHasher,
+{
+    // probability P(D|H), and P(D|H) assumed for values not seen with H during training
+    Categorical(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::entries"))]
+        HashMap<D, Vec<(H, F)>, S>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::entries"))]
+        HashMap<H, F, S>,
+    ),
+    // mean and variance of the data given hypothesis
+    Gaussian(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::entries"))]
+        HashMap<H, (F, F), S>,
+    ),
+    // probability P(D|H) of each token, and P(D|H) assumed for tokens not seen with H
+    Tokens(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::entries"))]
+        HashMap<D, Vec<(H, F)>, S>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::entries"))]
+        HashMap<H, F, S>,
+    ),
+}
+
+// The model of a field as stored by a MixedClassifier, with each hypothesis as an id (see
+// interning.rs).
+#[derive(Clone, Debug)]
+enum FieldTable<D, F, S> {
+    Categorical(HashMap<D, Vec<(u32, F)>, S>, Vec<F>),
+    // a single row of the parameters of every hypothesis, so that it is scored as a value
+    // that every hypothesis has seen
+    Gaussian(Vec<(u32, (F, F))>),
+    Tokens(HashMap<D, Vec<(u32, F)>, S>, Vec<F>),
+}
+
+#[derive(Clone, Debug)]
+pub struct MixedClassifier<D, H, F = f64, S = RandomState>
+where
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    F: Float,
+    S: BuildHasher,
+{
+    schema: Vec<Field>,
+    // each hypothesis, indexed by its id (see interning.rs)
+    hypotheses: Vec<H>,
+    // probability of each hypothesis, by id
+    log_priors: Vec<F>,
+    fields: Vec<FieldTable<D, F, S>>,
+    unseen_policy: UnseenPolicy,
+}
+
+impl<D, F, S> PartialEq for FieldTable<D, F, S>
+where
+    D: Eq + Hash,
+    F: Float,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                FieldTable::Categorical(lhs, lhs_unseen),
+                FieldTable::Categorical(rhs, rhs_unseen),
+            )
+            | (FieldTable::Tokens(lhs, lhs_unseen), FieldTable::Tokens(rhs, rhs_unseen)) => {
+                lhs == rhs && lhs_unseen == rhs_unseen
+            }
+            (FieldTable::Gaussian(lhs), FieldTable::Gaussian(rhs)) => lhs == rhs,
+            _ => false,
+        }
+    }
+}
+
+impl<D, F, S> FieldTable<D, F, S>
+where
+    D: Clone + Eq + Hash,
+    F: Float,
+    S: BuildHasher + Default,
+{
+    // Replace the hypotheses of a field model with their ids, or None if a hypothesis has no id
+    // or a hypothesis has no probability for unseen values.
+    fn intern<H: Clone + Eq + Hash>(
+        model: FieldModel<D, H, F, S>,
+        hypotheses: &[H],
+        ids: &HashMap<H, u32, S>,
+    ) -> Option<Self> {
+        Some(match model {
+            FieldModel::Categorical(log_likelihoods, log_unseen) => FieldTable::Categorical(
+                intern_rows(log_likelihoods, ids)?,
+                intern_values(&log_unseen, hypotheses)?,
+            ),
+            FieldModel::Gaussian(parameters) => {
+                let mut row = parameters
+                    .into_iter()
+                    .map(|(h, parameters)| Some((*ids.get(&h)?, parameters)))
+                    .collect::<Option<Vec<_>>>()?;
+                row.sort_by_key(|(id, _)| *id);
+                FieldTable::Gaussian(row)
+            }
+            FieldModel::Tokens(log_likelihoods, log_unseen) => FieldTable::Tokens(
+                intern_rows(log_likelihoods, ids)?,
+                intern_values(&log_unseen, hypotheses)?,
+            ),
+        })
+    }
+
+    // Key the model of the field by hypothesis.
+    #[cfg(feature = "serde")]
+    fn extern_hypotheses<H: Clone + Eq + Hash>(&self, hypotheses: &[H]) -> FieldModel<D, H, F, S> {
+        match self {
+            FieldTable::Categorical(log_likelihoods, log_unseen) => FieldModel::Categorical(
+                extern_rows(log_likelihoods, hypotheses),
+                extern_values(log_unseen, hypotheses),
+            ),
+            FieldTable::Gaussian(row) => FieldModel::Gaussian(
+                row.iter()
+                    .map(|(id, parameters)| (hypotheses[*id as usize].clone(), *parameters))
+                    .collect(),
+            ),
+            FieldTable::Tokens(log_likelihoods, log_unseen) => FieldModel::Tokens(
+                extern_rows(log_likelihoods, hypotheses),
+                extern_values(log_unseen, hypotheses),
+            ),
+        }
+    }
+
+    // Convert the probabilities and parameters of the model to another floating point type.
+    fn cast<G: Float>(&self) -> FieldTable<D, G, S> {
+        match self {
+            FieldTable::Categorical(log_likelihoods, log_unseen) => {
+                FieldTable::Categorical(cast_rows(log_likelihoods), cast_values(log_unseen))
+            }
+            FieldTable::Gaussian(row) => FieldTable::Gaussian(
+                row.iter()
+                    .map(|(id, (mean, variance))| (*id, (cast(*mean), cast(*variance))))
+                    .collect(),
+            ),
+            FieldTable::Tokens(log_likelihoods, log_unseen) => {
+                FieldTable::Tokens(cast_rows(log_likelihoods), cast_values(log_unseen))
+            }
+        }
+    }
+}
+
+impl<D, H, F, S> PartialEq for MixedClassifier<D, H, F, S>
+where
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    F: Float,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.schema == other.schema
+            && self.hypotheses == other.hypotheses
+            && self.log_priors == other.log_priors
+            && self.fields == other.fields
+            && self.unseen_policy == other.unseen_policy
+    }
+}
+
+impl<D, H, F, S> MixedClassifier<D, H, F, S>
+where
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    F: Float,
+    S: BuildHasher + Default,
+{
+    /// Create a new Classifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - the distribution of each field in a record
+    /// * `log_priors` - probability of hypothesis
+    /// * `fields` - the model of data given hypothesis, for each field in the schema
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(MixedClassifier)` - the classifier.
+    /// * `Err(Error::InconsistentModel)` - the fields do not match the schema, or refer to a
+    ///   hypothesis with no probability.
+    ///
+    pub(crate) fn new(
+        schema: Vec<Field>,
+        log_priors: HashMap<H, F, S>,
+        fields: Vec<FieldModel<D, H, F, S>>,
+    ) -> Result<Self, Error> {
+        let matches_schema = fields.len() == schema.len()
+            && fields.iter().zip(&schema).all(|(model, field)| {
+                matches!(
+                    (model, field),
+                    (FieldModel::Categorical(..), Field::Categorical)
+                        | (FieldModel::Gaussian(_), Field::Gaussian)
+                        | (FieldModel::Tokens(..), Field::Tokens)
+                )
+            });
+        if !matches_schema {
+            return Err(Error::InconsistentModel);
+        }
+
+        let (hypotheses, ids) = hypothesis_table(&log_priors);
+        let fields = fields
+            .into_iter()
+            .map(|model| FieldTable::intern(model, &hypotheses, &ids))
+            .collect::<Option<_>>()
+            .ok_or(Error::InconsistentModel)?;
+        Ok(MixedClassifier {
+            schema,
+            log_priors: intern_values(&log_priors, &hypotheses).unwrap(),
+            hypotheses,
+            fields,
+            unseen_policy: UnseenPolicy::default(),
+        })
+    }
+
+    /// Set how categorical values and tokens that a hypothesis did not see during training are
+    /// scored.
+    ///
+    /// # Arguments
+    ///
+    /// * `unseen_policy` - the policy applied to unseen values
+    ///
+    pub fn with_unseen_policy(mut self, unseen_policy: UnseenPolicy) -> Self {
+        self.unseen_policy = unseen_policy;
+        self
+    }
+
+    /// Convert the probabilities and parameters of the Classifier to another floating point
+    /// type, for example `f32` to halve the memory used by the model.
+    ///
+    /// # Return Value
+    ///
+    /// * `MixedClassifier` type
+    ///
+    pub fn cast<G: Float>(&self) -> MixedClassifier<D, H, G, S> {
+        MixedClassifier {
+            schema: self.schema.clone(),
+            hypotheses: self.hypotheses.clone(),
+            log_priors: cast_values(&self.log_priors),
+            fields: self.fields.iter().map(FieldTable::cast).collect(),
+            unseen_policy: self.unseen_policy,
+        }
+    }
+
+    /// Classify an unknown record.
+    ///
+    /// # Arguments
+    ///
+    /// * `record` - the value of each field in the schema
+    ///
+    /// # Return Value
+    ///
+    /// * `Results` type
+    ///
+    /// # Panics
+    ///
+    /// * if the record does not match the schema, or the policy is `UnseenPolicy::Error` and a
+    ///   value was not seen by any hypothesis, see `try_classify`.
+    ///
+    pub fn classify(&self, record: &[Value<D>]) -> Results<H, F> {
+        self.try_classify(record).unwrap()
+    }
+
+    /// Classify an unknown record, failing if it does not match the schema or if the
+    /// `UnseenPolicy` rejects a value.
+    ///
+    /// The log-likelihoods of all fields are summed before normalisation, following the 'naive'
+    /// assumption that the fields are independent given the hypothesis.
+    ///
+    /// # Arguments
+    ///
+    /// * `record` - the value of each field in the schema
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(Results)` - the classification results.
+    /// * `Err(Error::SchemaMismatch)` - the record does not match the schema.
+    /// * `Err(Error::UnseenValue)` - a value was not seen by any hypothesis during training.
+    ///   The position is that of the field in the schema.
+    ///
+    pub fn try_classify(&self, record: &[Value<D>]) -> Result<Results<H, F>, Error> {
+        check_schema(&self.schema, record)?;
+
+        // Accumulate product of likelihoods, by hypothesis id. Each value added to the scratch
+        // space needs its own position, as a Tokens field adds many values.
+        let mut scratch = Scratch::new();
+        scratch.reset(self.hypotheses.len());
+        let mut position = 0;
+        for (i, (model, value)) in self.fields.iter().zip(record).enumerate() {
+            match (model, value) {
+                (FieldTable::Categorical(rows, log_unseen), Value::Categorical(d)) => {
+                    self.add(&mut scratch, position, rows, log_unseen, d, i)?;
+                    position += 1;
+                }
+                (FieldTable::Gaussian(row), Value::Gaussian(x)) => {
+                    let x = cast(*x);
+                    scratch.add(
+                        position,
+                        row.iter().copied(),
+                        &[],
+                        self.unseen_policy,
+                        |(mean, variance)| log2_density(x, mean, variance),
+                    );
+                    position += 1;
+                }
+                (FieldTable::Tokens(rows, log_unseen), Value::Tokens(tokens)) => {
+                    for d in tokens.iter() {
+                        self.add(&mut scratch, position, rows, log_unseen, d, i)?;
+                        position += 1;
+                    }
+                }
+                // The record and the fields were both checked against the schema.
+                _ => unreachable!(),
+            }
+        }
+
+        let mut results = Results::default();
+        scratch.posteriors_into(&self.hypotheses, &self.log_priors, &mut results);
+        Ok(results)
+    }
+
+    // Add log2(p(d|h)) for a single discrete value to the scratch space, applying the
+    // UnseenPolicy if no hypothesis saw the value. The field is reported as the position of
+    // the value if the policy rejects it.
+    fn add(
+        &self,
+        scratch: &mut Scratch<F>,
+        position: usize,
+        rows: &HashMap<D, Vec<(u32, F)>, S>,
+        log_unseen: &[F],
+        d: &D,
+        field: usize,
+    ) -> Result<(), Error> {
+        let found: &[(u32, F)] = match rows.get(d) {
+            Some(found) => found,
+            None => match self.unseen_policy {
+                UnseenPolicy::Skip => return Ok(()),
+                UnseenPolicy::Error => return Err(Error::UnseenValue { position: field }),
+                _ => &[],
+            },
+        };
+        scratch.add(
+            position,
+            found.iter().copied(),
+            log_unseen,
+            self.unseen_policy,
+            |p| p,
+        );
+        Ok(())
+    }
+}
+
+// The serialized form of a MixedClassifier, which refers to hypotheses by value.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(bound(
+    serialize = "D: serde::Serialize, H: serde::Serialize, F: serde::Serialize",
+    deserialize = "D: serde::Deserialize<'de>, H: serde::Deserialize<'de>, \
+                   F: serde::Deserialize<'de>, S: BuildHasher + Default"
+))]
+struct MixedClassifierEntries<D, H, F, S>
+where
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    F: Float,
+    S: BuildHasher,
+{
+    schema: Vec<Field>,
+    #[serde(with = "crate::serialization::entries")]
+    log_priors: HashMap<H, F, S>,
+    fields: Vec<FieldModel<D, H, F, S>>,
+    unseen_policy: UnseenPolicy,
+}
+
+#[cfg(feature = "serde")]
+impl<D, H, F, S> serde::Serialize for MixedClassifier<D, H, F, S>
+where
+    D: Clone + Eq + Hash + serde::Serialize,
+    H: Clone + Eq + Hash + serde::Serialize,
+    F: Float + serde::Serialize,
+    S: BuildHasher + Default,
+{
+    fn serialize<T: serde::Serializer>(&self, serializer: T) -> Result<T::Ok, T::Error> {
+        MixedClassifierEntries {
+            schema: self.schema.clone(),
+            log_priors: extern_values::<_, _, S>(&self.log_priors, &self.hypotheses),
+            fields: self
+                .fields
+                .iter()
+                .map(|field| field.extern_hypotheses(&self.hypotheses))
+                .collect(),
+            unseen_policy: self.unseen_policy,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, D, H, F, S> serde::Deserialize<'de> for MixedClassifier<D, H, F, S>
+where
+    D: Clone + Eq + Hash + serde::Deserialize<'de>,
+    H: Clone + Eq + Hash + serde::Deserialize<'de>,
+    F: Float + serde::Deserialize<'de>,
+    S: BuildHasher + Default,
+{
+    fn deserialize<T: serde::Deserializer<'de>>(deserializer: T) -> Result<Self, T::Error> {
+        let entries = MixedClassifierEntries::<D, H, F, S>::deserialize(deserializer)?;
+        let classifier = MixedClassifier::new(entries.schema, entries.log_priors, entries.fields)
+            .map_err(serde::de::Error::custom)?;
+        Ok(classifier.with_unseen_policy(entries.unseen_policy))
+    }
+}
@@ -0,0 +1,341 @@
+use crate::error::Error;
+use crate::field::{check_schema, Field, Value};
+use crate::likelihoods::{likelihoods, priors, unseen_likelihoods};
+use crate::mixedclassifier::{FieldModel, MixedClassifier};
+use crate::moments::{variance_epsilon, Moments, DEFAULT_VARIANCE_SMOOTHING};
+use crate::smoothing::Smoothing;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::{BuildHasher, Hash};
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "D: serde::Serialize, H: serde::Serialize",
+        deserialize = "D: serde::Deserialize<'de>, H: serde::Deserialize<'de>, \
+                       S: BuildHasher + Default"
+    ))
+)]
+enum FieldCounts<D, H, S>
+where
+    D: Clone + Debug + Eq + Hash,
+    H: Clone + Debug + Eq + Hash,
+    S: BuildHasher,
+{
+    Categorical(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::counts"))]
+        HashMap<(D, H), f64, S>,
+    ),
+    Gaussian(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::entries"))]
+        HashMap<H, Moments, S>,
+    ),
+    // The count of |h| is the number of tokens seen with h, not the number of records.
+    Tokens(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::counts"))]
+        HashMap<H, f64, S>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::counts"))]
+        HashMap<(D, H), f64, S>,
+    ),
+}
+
+impl<D, H, S> PartialEq for FieldCounts<D, H, S>
+where
+    D: Clone + Debug + Eq + Hash,
+    H: Clone + Debug + Eq + Hash,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (FieldCounts::Categorical(lhs), FieldCounts::Categorical(rhs)) => lhs == rhs,
+            (FieldCounts::Gaussian(lhs), FieldCounts::Gaussian(rhs)) => lhs == rhs,
+            (FieldCounts::Tokens(lhs_h, lhs_joint), FieldCounts::Tokens(rhs_h, rhs_joint)) => {
+                lhs_h == rhs_h && lhs_joint == rhs_joint
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "D: serde::Serialize, H: serde::Serialize",
+        deserialize = "D: serde::Deserialize<'de>, H: serde::Deserialize<'de>, \
+                       S: BuildHasher + Default"
+    ))
+)]
+pub struct MixedLearner<D, H, S = RandomState>
+where
+    D: Clone + Debug + Eq + Hash,
+    H: Clone + Debug + Eq + Hash,
+    S: BuildHasher,
+{
+    schema: Vec<Field>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::counts"))]
+    count_hypotheses: HashMap<H, f64, S>,
+    counts: Vec<FieldCounts<D, H, S>>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::count"))]
+    count_total: f64,
+}
+
+impl<D, H, S> PartialEq for MixedLearner<D, H, S>
+where
+    D: Clone + Debug + Eq + Hash,
+    H: Clone + Debug + Eq + Hash,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.schema == other.schema
+            && self.count_hypotheses == other.count_hypotheses
+            && self.counts == other.counts
+            && self.count_total == other.count_total
+    }
+}
+
+impl<D: Clone + Debug + Eq + Hash, H: Clone + Debug + Eq + Hash> MixedLearner<D, H> {
+    /// Create a new Learner for records with the given schema.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - the distribution of each field in a record
+    ///
+    pub fn new(schema: &[Field]) -> Self {
+        Self::with_default_hasher(schema)
+    }
+}
+
+impl<D, H, S> MixedLearner<D, H, S>
+where
+    D: Clone + Debug + Eq + Hash,
+    H: Clone + Debug + Eq + Hash,
+    S: BuildHasher + Default,
+{
+    /// Create a new Learner for records with the given schema, whose maps use the hasher type
+    /// `S`.
+    ///
+    /// Unlike `HashMap::with_hasher`, this takes no hasher instance: every map, including the
+    /// maps of the Classifiers made by the Learner, is created with `S::default()`, so the
+    /// hasher is chosen by its type and cannot be seeded per instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - the distribution of each field in a record
+    ///
+    pub fn with_default_hasher(schema: &[Field]) -> Self {
+        MixedLearner {
+            schema: schema.to_vec(),
+            count_hypotheses: HashMap::default(),
+            counts: schema
+                .iter()
+                .map(|field| match field {
+                    Field::Categorical => FieldCounts::Categorical(HashMap::default()),
+                    Field::Gaussian => FieldCounts::Gaussian(HashMap::default()),
+                    Field::Tokens => FieldCounts::Tokens(HashMap::default(), HashMap::default()),
+                })
+                .collect(),
+            count_total: 0.0,
+        }
+    }
+
+    /// Update the Learner with a single record of training data for a single hypothesis.
+    ///
+    /// # Arguments
+    ///
+    /// * `record` - the value of each field in the schema
+    /// * `hypothesis` - the target hypothesis/label/category/classification for the data.
+    ///
+    /// # Panics
+    ///
+    /// * if the record does not match the schema, see `try_update`.
+    ///
+    pub fn update(&mut self, record: &[Value<D>], hypothesis: H) -> &mut Self {
+        self.try_update(record, hypothesis).unwrap()
+    }
+
+    /// Update the Learner with a single record of training data for a single hypothesis, failing
+    /// if the record does not match the schema.
+    ///
+    /// # Arguments
+    ///
+    /// * `record` - the value of each field in the schema
+    /// * `hypothesis` - the target hypothesis/label/category/classification for the data.
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(&mut Self)` - the record was learned.
+    /// * `Err(Error::SchemaMismatch)` - the record does not match the schema and was not learned.
+    /// * `Err(Error::InconsistentModel)` - the Learner was deserialised from corrupt data, and the
+    ///   record was not learned.
+    ///
+    pub fn try_update(&mut self, record: &[Value<D>], hypothesis: H) -> Result<&mut Self, Error> {
+        check_schema(&self.schema, record)?;
+        self.check_counts()?;
+        for (counts, value) in self.counts.iter_mut().zip(record) {
+            match (counts, value) {
+                (FieldCounts::Categorical(count_joint), Value::Categorical(d)) => {
+                    *count_joint
+                        .entry((d.clone(), hypothesis.clone()))
+                        .or_insert(0.0) += 1.0;
+                }
+                (FieldCounts::Gaussian(moments), Value::Gaussian(x)) => {
+                    moments.entry(hypothesis.clone()).or_default().update(*x);
+                }
+                (FieldCounts::Tokens(count_hypotheses, count_joint), Value::Tokens(tokens)) => {
+                    for d in tokens.iter() {
+                        *count_joint
+                            .entry((d.clone(), hypothesis.clone()))
+                            .or_insert(0.0) += 1.0;
+                    }
+                    *count_hypotheses.entry(hypothesis.clone()).or_insert(0.0) +=
+                        tokens.len() as f64;
+                }
+                _ => unreachable!("record and counts were checked against the schema"),
+            }
+        }
+        *self.count_hypotheses.entry(hypothesis).or_insert(0.0) += 1.0;
+        self.count_total += 1.0;
+        Ok(self)
+    }
+
+    /// Make a classifier based on a snapshot of the current Learner's training.
+    ///
+    /// No smoothing is applied, see `make_classifier_with_smoothing`.
+    ///
+    /// # Return Value
+    ///
+    /// * `MixedClassifier` type
+    ///
+    pub fn make_classifier(&mut self) -> MixedClassifier<D, H, f64, S> {
+        self.make_classifier_with_smoothing(Smoothing::None)
+    }
+
+    /// Make a classifier based on a snapshot of the current Learner's training, estimating
+    /// likelihoods of categorical and token fields with the given smoothing.
+    ///
+    /// Gaussian fields use `GaussianLearner::DEFAULT_VARIANCE_SMOOTHING`.
+    ///
+    /// # Arguments
+    ///
+    /// * `smoothing` - the smoothing applied to the likelihood of categorical and token fields
+    ///
+    /// # Return Value
+    ///
+    /// * `MixedClassifier` type
+    ///
+    /// # Panics
+    ///
+    /// * if the smoothing alpha is not positive and finite, or if the Learner was deserialised
+    ///   from corrupt data, see `try_make_classifier_with_smoothing`.
+    ///
+    pub fn make_classifier_with_smoothing(
+        &mut self,
+        smoothing: Smoothing,
+    ) -> MixedClassifier<D, H, f64, S> {
+        self.classifier(smoothing).unwrap()
+    }
+
+    /// Make a classifier based on a snapshot of the current Learner's training, failing if the
+    /// Learner has no training data or is inconsistent.
+    ///
+    /// No smoothing is applied, see `try_make_classifier_with_smoothing`.
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(MixedClassifier)` - the classifier.
+    /// * `Err(Error::EmptyModel)` - the Learner has no training data.
+    /// * `Err(Error::InconsistentModel)` - the Learner was deserialised from corrupt data.
+    ///
+    pub fn try_make_classifier(&mut self) -> Result<MixedClassifier<D, H, f64, S>, Error> {
+        self.try_make_classifier_with_smoothing(Smoothing::None)
+    }
+
+    /// Make a classifier based on a snapshot of the current Learner's training, estimating
+    /// likelihoods of categorical and token fields with the given smoothing, failing if the
+    /// Learner has no training data or is inconsistent.
+    ///
+    /// # Arguments
+    ///
+    /// * `smoothing` - the smoothing applied to the likelihood of categorical and token fields
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(MixedClassifier)` - the classifier.
+    /// * `Err(Error::EmptyModel)` - the Learner has no training data.
+    /// * `Err(Error::InvalidSmoothing)` - the smoothing alpha is not positive and finite.
+    /// * `Err(Error::InconsistentModel)` - the Learner was deserialised from corrupt data.
+    ///
+    pub fn try_make_classifier_with_smoothing(
+        &mut self,
+        smoothing: Smoothing,
+    ) -> Result<MixedClassifier<D, H, f64, S>, Error> {
+        if self.count_hypotheses.is_empty() {
+            return Err(Error::EmptyModel);
+        }
+        self.classifier(smoothing)
+    }
+
+    // Make a classifier, failing if the smoothing is invalid or the counts are inconsistent.
+    fn classifier(&self, smoothing: Smoothing) -> Result<MixedClassifier<D, H, f64, S>, Error> {
+        self.check_counts()?;
+        smoothing.check()?;
+        let log_priors: HashMap<H, f64, S> = priors(&self.count_hypotheses, self.count_total)?;
+
+        let epsilon = variance_epsilon(
+            self.counts
+                .iter()
+                .flat_map(|counts| match counts {
+                    FieldCounts::Gaussian(moments) => Some(moments.values()),
+                    _ => None,
+                })
+                .flatten(),
+            DEFAULT_VARIANCE_SMOOTHING,
+        );
+
+        let fields: Vec<FieldModel<D, H, f64, S>> = self
+            .counts
+            .iter()
+            .map(|counts| match counts {
+                FieldCounts::Categorical(count_joint) => Ok(FieldModel::Categorical(
+                    likelihoods(&self.count_hypotheses, count_joint, smoothing)?,
+                    unseen_likelihoods(&self.count_hypotheses, count_joint, smoothing),
+                )),
+                FieldCounts::Gaussian(moments) => Ok(FieldModel::Gaussian(
+                    moments
+                        .iter()
+                        .map(|(h, m)| (h.clone(), (m.mean(), m.variance() + epsilon)))
+                        .collect(),
+                )),
+                FieldCounts::Tokens(count_hypotheses, count_joint) => Ok(FieldModel::Tokens(
+                    likelihoods(count_hypotheses, count_joint, smoothing)?,
+                    unseen_likelihoods(count_hypotheses, count_joint, smoothing),
+                )),
+            })
+            .collect::<Result<_, Error>>()?;
+
+        MixedClassifier::new(self.schema.clone(), log_priors, fields)
+    }
+
+    // Check that the counts of each field match the schema.
+    fn check_counts(&self) -> Result<(), Error> {
+        let consistent = self.counts.len() == self.schema.len()
+            && self.counts.iter().zip(&self.schema).all(|(counts, field)| {
+                matches!(
+                    (counts, field),
+                    (FieldCounts::Categorical(_), Field::Categorical)
+                        | (FieldCounts::Gaussian(_), Field::Gaussian)
+                        | (FieldCounts::Tokens(..), Field::Tokens)
+                )
+            });
+        if consistent {
+            Ok(())
+        } else {
+            Err(Error::InconsistentModel)
+        }
+    }
+}
@@ -0,0 +1,406 @@
+// A compact, versioned binary file format for classifiers.
+//
+// All numbers are little endian. The layout mirrors the in-memory layout of the
+// classifiers (see interning.rs): hypotheses are stored once, in a table, and each likelihood
+// row refers to a hypothesis by its index in the table, rather than repeating the encoded
+// hypothesis.
+//
+//      magic               [u8; 4]     b"NBCM"
+//      version             u32         FORMAT_VERSION
+//      kind                u8          KIND_STREAM or KIND_FIXED
+//      positions           u32         1 for a StreamClassifier, DS for a FixedClassifier
+//      unseen policy       u8, f64     tag and penalty (the penalty is 0.0 if unused)
+//      hypotheses          u32         |H|
+//          hypothesis      H           repeated |H| times, with:
+//          log prior       f64
+//      for each position:
+//          log unseen      f64         repeated |H| times, in hypothesis table order
+//          vocabulary      u32         |V|
+//              value       D           repeated |V| times, with:
+//              row length  u32
+//                  index   u32         repeated row length times, in increasing order, with:
+//                  log     f64
+//
+// Floating point values are stored as the IEEE 754 bit patterns of f64 values, so a model
+// round-trips exactly whether its probabilities are f32 or f64.
+// Hypotheses and values are written in strictly increasing order of their encoded bytes, so the
+// same model is always encoded in the same way, whatever the iteration order of its maps, and
+// a file that repeats a hypothesis or value is rejected. The table is in a different order to
+// the ids of a Classifier, which follow the stable hash of the hypotheses, so decoding hashes
+// each hypothesis once and maps the indices in each row to ids.
+//
+// A frozen model (see frozenclassifier.rs) shares the header, but lays out a single position
+// as flat arrays that can be searched in place.
+use crate::error::Error;
+use crate::interning::intern_order;
+use crate::likelihoods::LogLikelihoods;
+use crate::precision::cast;
+use crate::unseen::UnseenPolicy;
+use num_traits::Float;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+
+const MAGIC: &[u8; 4] = b"NBCM";
+const FORMAT_VERSION: u32 = 1;
+pub(crate) const KIND_STREAM: u8 = 1;
+pub(crate) const KIND_FIXED: u8 = 2;
+pub(crate) const KIND_FROZEN: u8 = 3;
+
+/// A data value or hypothesis that can be stored in a model file.
+///
+/// Implemented for the primitive integer types, `bool`, `char`, `String` and `&str`. Strings are
+/// stored as their length followed by their UTF-8 bytes, so `String` and `&str` have the same
+/// encoding.
+///
+/// The lifetime is that of the bytes a value is decoded from, so that a `&str` can borrow its
+/// text from a `FrozenClassifier` model rather than copying it. Every other type implements
+/// `ModelKey` for any lifetime, see `ModelKeyOwned`.
+pub trait ModelKey<'a>: Sized {
+    /// Append the binary encoding of this value to `out`.
+    fn encode(&self, out: &mut Vec<u8>);
+
+    /// Decode a value from the start of `input`, advancing `input` past it.
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(Self)` - the decoded value.
+    /// * `Err(Error::Truncated)` - `input` ends before the value is complete.
+    /// * `Err(Error::InvalidFormat)` - `input` does not contain a valid value.
+    ///
+    fn decode(input: &mut &'a [u8]) -> Result<Self, Error>;
+}
+
+/// A `ModelKey` that does not borrow from the bytes it is decoded from, as required to save and
+/// load a `StreamClassifier` or `FixedClassifier`.
+pub trait ModelKeyOwned: for<'a> ModelKey<'a> {}
+
+impl<T> ModelKeyOwned for T where T: for<'a> ModelKey<'a> {}
+
+macro_rules! impl_model_key {
+    ($($t:ty),*) => {
+        $(
+            impl<'a> ModelKey<'a> for $t {
+                fn encode(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn decode(input: &mut &'a [u8]) -> Result<Self, Error> {
+                    let bytes = take(input, std::mem::size_of::<$t>())?;
+                    Ok(<$t>::from_le_bytes(bytes.try_into().unwrap()))
+                }
+            }
+        )*
+    };
+}
+
+impl_model_key!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+impl<'a> ModelKey<'a> for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+
+    fn decode(input: &mut &'a [u8]) -> Result<Self, Error> {
+        match u8::decode(input)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(Error::InvalidFormat),
+        }
+    }
+}
+
+impl<'a> ModelKey<'a> for char {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as u32).encode(out);
+    }
+
+    fn decode(input: &mut &'a [u8]) -> Result<Self, Error> {
+        char::from_u32(u32::decode(input)?).ok_or(Error::InvalidFormat)
+    }
+}
+
+impl<'a> ModelKey<'a> for String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_str(self, out);
+    }
+
+    fn decode(input: &mut &'a [u8]) -> Result<Self, Error> {
+        decode_str(input).map(str::to_owned)
+    }
+}
+
+impl<'a: 'b, 'b> ModelKey<'a> for &'b str {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_str(self, out);
+    }
+
+    fn decode(input: &mut &'a [u8]) -> Result<Self, Error> {
+        decode_str(input)
+    }
+}
+
+fn encode_str(s: &str, out: &mut Vec<u8>) {
+    encode_len(s.len(), out);
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn decode_str<'a>(input: &mut &'a [u8]) -> Result<&'a str, Error> {
+    let len = decode_len(input)?;
+    std::str::from_utf8(take(input, len)?).map_err(|_| Error::InvalidFormat)
+}
+
+// The contents of a model file in the layout of the classifiers, with each hypothesis as an id
+// (see interning.rs), and one entry in each of the last two Vecs per input array position.
+pub(crate) struct Model<D, H, F, S> {
+    pub(crate) hypotheses: Vec<H>,
+    pub(crate) log_priors: Vec<F>,
+    pub(crate) log_likelihoods: Vec<LogLikelihoods<D, u32, F, S>>,
+    pub(crate) log_unseen: Vec<Vec<F>>,
+    pub(crate) unseen_policy: UnseenPolicy,
+}
+
+// Split off the first n bytes of input.
+pub(crate) fn take<'a>(input: &mut &'a [u8], n: usize) -> Result<&'a [u8], Error> {
+    if input.len() < n {
+        return Err(Error::Truncated);
+    }
+    let (head, tail) = input.split_at(n);
+    *input = tail;
+    Ok(head)
+}
+
+pub(crate) fn encode_len(len: usize, out: &mut Vec<u8>) {
+    (len as u32).encode(out);
+}
+
+pub(crate) fn decode_len(input: &mut &[u8]) -> Result<usize, Error> {
+    Ok(u32::decode(input)? as usize)
+}
+
+// Write the magic number, format version, model kind, number of positions and unseen policy.
+pub(crate) fn encode_header(
+    kind: u8,
+    positions: usize,
+    unseen_policy: UnseenPolicy,
+    out: &mut Vec<u8>,
+) {
+    out.extend_from_slice(MAGIC);
+    FORMAT_VERSION.encode(out);
+    kind.encode(out);
+    encode_len(positions, out);
+
+    let (tag, penalty) = match unseen_policy {
+        UnseenPolicy::Penalty(penalty) => (0u8, penalty),
+        UnseenPolicy::Skip => (1, 0.0),
+        UnseenPolicy::Smoothed => (2, 0.0),
+        UnseenPolicy::Error => (3, 0.0),
+    };
+    tag.encode(out);
+    encode_float(penalty, out);
+}
+
+// Read the header written by encode_header, returning the number of positions and the policy.
+pub(crate) fn decode_header(input: &mut &[u8], kind: u8) -> Result<(usize, UnseenPolicy), Error> {
+    if take(input, MAGIC.len())? != MAGIC {
+        return Err(Error::InvalidFormat);
+    }
+    let version = u32::decode(input)?;
+    if version == 0 {
+        return Err(Error::InvalidFormat);
+    }
+    if version > FORMAT_VERSION {
+        return Err(Error::UnsupportedVersion { version });
+    }
+    if u8::decode(input)? != kind {
+        return Err(Error::InvalidFormat);
+    }
+    let positions = decode_len(input)?;
+
+    let tag = u8::decode(input)?;
+    let penalty = decode_float(input)?;
+    let unseen_policy = match tag {
+        0 => UnseenPolicy::Penalty(penalty),
+        1 => UnseenPolicy::Skip,
+        2 => UnseenPolicy::Smoothed,
+        3 => UnseenPolicy::Error,
+        _ => return Err(Error::InvalidFormat),
+    };
+    Ok((positions, unseen_policy))
+}
+
+// The positions of keys in order of their encoding, which is a deterministic order for any
+// ModelKey. The keys are encoded into a single buffer.
+pub(crate) fn encoding_order<'a, 'k, K: ModelKey<'k> + 'a>(
+    keys: impl Iterator<Item = &'a K>,
+) -> Vec<usize> {
+    let mut buffer = Vec::new();
+    let mut ends = Vec::new();
+    for k in keys {
+        k.encode(&mut buffer);
+        ends.push(buffer.len());
+    }
+    let encoding = |i: usize| &buffer[if i == 0 { 0 } else { ends[i - 1] }..ends[i]];
+    let mut order: Vec<usize> = (0..ends.len()).collect();
+    order.sort_by(|lhs, rhs| encoding(*lhs).cmp(encoding(*rhs)));
+    order
+}
+
+// Decode a key that must follow the previous key in order of encoding, so that keys are
+// unique, and remember its encoding as the previous key.
+pub(crate) fn decode_sorted<'a, K: ModelKey<'a>>(
+    input: &mut &'a [u8],
+    previous: &mut Option<&'a [u8]>,
+) -> Result<K, Error> {
+    let start = *input;
+    let key = K::decode(input)?;
+    let encoding = &start[..start.len() - input.len()];
+    if previous.map_or(false, |previous| previous >= encoding) {
+        return Err(Error::InvalidFormat);
+    }
+    *previous = Some(encoding);
+    Ok(key)
+}
+
+// Write a probability of any floating point type as an f64.
+pub(crate) fn encode_float<F: Float>(x: F, out: &mut Vec<u8>) {
+    cast::<F, f64>(x).to_bits().encode(out);
+}
+
+// Read a probability written by encode_float.
+pub(crate) fn decode_float<F: Float>(input: &mut &[u8]) -> Result<F, Error> {
+    Ok(cast(f64::from_bits(u64::decode(input)?)))
+}
+
+// Encode a Classifier, given its hypothesis table and the likelihoods of each position.
+pub(crate) fn encode<D, H, F, S>(
+    kind: u8,
+    hypotheses: &[H],
+    log_priors: &[F],
+    log_likelihoods: &[LogLikelihoods<D, u32, F, S>],
+    log_unseen: &[Vec<F>],
+    unseen_policy: UnseenPolicy,
+) -> Vec<u8>
+where
+    D: ModelKeyOwned,
+    H: ModelKeyOwned,
+    F: Float,
+    S: BuildHasher,
+{
+    let mut out = Vec::new();
+    encode_header(kind, log_likelihoods.len(), unseen_policy, &mut out);
+
+    // The table is written in order of encoding, so the index of each id in the file differs.
+    let order = encoding_order(hypotheses.iter());
+    let mut indices = vec![0; order.len()];
+    for (index, id) in order.iter().enumerate() {
+        indices[*id] = index as u32;
+    }
+    encode_len(hypotheses.len(), &mut out);
+    for id in &order {
+        hypotheses[*id].encode(&mut out);
+        encode_float(log_priors[*id], &mut out);
+    }
+
+    for (likelihoods, unseen) in log_likelihoods.iter().zip(log_unseen) {
+        for id in &order {
+            encode_float(unseen[*id], &mut out);
+        }
+        encode_len(likelihoods.len(), &mut out);
+        let values: Vec<(&D, &Vec<(u32, F)>)> = likelihoods.iter().collect();
+        for i in encoding_order(values.iter().map(|(d, _)| *d)) {
+            let (d, row) = values[i];
+            d.encode(&mut out);
+            let mut row: Vec<(u32, F)> = row
+                .iter()
+                .map(|(id, p)| (indices[*id as usize], *p))
+                .collect();
+            row.sort_by_key(|(index, _)| *index);
+            encode_len(row.len(), &mut out);
+            for (index, p) in row {
+                index.encode(&mut out);
+                encode_float(p, &mut out);
+            }
+        }
+    }
+    out
+}
+
+// Decode a Classifier written by encode, checking that the model is complete and consistent.
+pub(crate) fn decode<'a, D, H, F, S>(bytes: &'a [u8], kind: u8) -> Result<Model<D, H, F, S>, Error>
+where
+    D: Eq + Hash + ModelKey<'a>,
+    H: Hash + ModelKey<'a>,
+    F: Float,
+    S: BuildHasher + Default,
+{
+    let mut input = bytes;
+    let (positions, unseen_policy) = decode_header(&mut input, kind)?;
+
+    // Each hypothesis is followed by an 8 byte prior, so a corrupt count cannot cause a huge
+    // allocation.
+    let count = decode_len(&mut input)?;
+    let mut table: Vec<H> = Vec::with_capacity(count.min(input.len() / 8));
+    let mut table_priors: Vec<F> = Vec::with_capacity(table.capacity());
+    let mut previous = None;
+    for _ in 0..count {
+        table.push(decode_sorted(&mut input, &mut previous)?);
+        table_priors.push(decode_float(&mut input)?);
+    }
+
+    // Put the table in order of id, and map the index of each hypothesis in the file to its id.
+    let order = intern_order(&table);
+    let mut ids = vec![0; count];
+    for (id, index) in order.iter().enumerate() {
+        ids[*index] = id as u32;
+    }
+    let log_priors: Vec<F> = order.iter().map(|index| table_priors[*index]).collect();
+    let mut table: Vec<Option<H>> = table.into_iter().map(Some).collect();
+    let hypotheses: Vec<H> = order
+        .iter()
+        .map(|index| table[*index].take().unwrap())
+        .collect();
+
+    let mut log_likelihoods: Vec<LogLikelihoods<D, u32, F, S>> = Vec::new();
+    let mut log_unseen: Vec<Vec<F>> = Vec::new();
+    for _ in 0..positions {
+        let mut unseen = vec![F::zero(); count];
+        for id in &ids {
+            unseen[*id as usize] = decode_float(&mut input)?;
+        }
+        let vocabulary = decode_len(&mut input)?;
+        // Each entry is at least 8 bytes, so a corrupt length cannot cause a huge allocation.
+        let mut likelihoods: LogLikelihoods<D, u32, F, S> =
+            HashMap::with_capacity_and_hasher(vocabulary.min(input.len() / 8), S::default());
+        let mut previous = None;
+        for _ in 0..vocabulary {
+            let d = decode_sorted(&mut input, &mut previous)?;
+            let len = decode_len(&mut input)?;
+            let mut row: Vec<(u32, F)> = Vec::with_capacity(len.min(count));
+            let mut next = 0;
+            for _ in 0..len {
+                // Indices increase, so each hypothesis appears at most once in a row.
+                let index = decode_len(&mut input)?;
+                if index < next || index >= count {
+                    return Err(Error::InvalidFormat);
+                }
+                next = index + 1;
+                row.push((ids[index], decode_float(&mut input)?));
+            }
+            row.sort_by_key(|(id, _)| *id);
+            likelihoods.insert(d, row);
+        }
+        log_likelihoods.push(likelihoods);
+        log_unseen.push(unseen);
+    }
+    if !input.is_empty() {
+        return Err(Error::InvalidFormat);
+    }
+
+    Ok(Model {
+        hypotheses,
+        log_priors,
+        log_likelihoods,
+        log_unseen,
+        unseen_policy,
+    })
+}
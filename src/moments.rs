@@ -0,0 +1,61 @@
+// Running mean and variance of a sequence of values, using Welford's algorithm.
+//
+// The naive approach of accumulating the sum and the sum of squares loses precision
+// when the variance is small relative to the mean, because the variance is then the
+// difference of two large, nearly equal numbers.
+// Welford's algorithm instead updates the mean and the sum of squared differences
+// from the mean (m2) incrementally:
+//
+//      delta = x - mean
+//      mean = mean + delta / count
+//      m2 = m2 + delta * (x - mean)
+//
+// The population variance is then m2 / count.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Moments {
+    count: f64,
+    mean: f64,
+    m2: f64,
+}
+
+impl Moments {
+    pub(crate) fn update(&mut self, x: f64) {
+        self.count += 1.0;
+        let delta = x - self.mean;
+        self.mean += delta / self.count;
+        self.m2 += delta * (x - self.mean);
+    }
+
+    pub(crate) fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    pub(crate) fn variance(&self) -> f64 {
+        if self.count > 0.0 {
+            self.m2 / self.count
+        } else {
+            0.0
+        }
+    }
+}
+
+// The default variance smoothing, as a fraction of the largest variance.
+pub(crate) const DEFAULT_VARIANCE_SMOOTHING: f64 = 1e-9;
+
+// Determine the amount added to every variance to avoid division by zero.
+//
+// A variance of zero would make the density infinite at the mean and zero elsewhere,
+// so a fraction of the largest variance is added to every variance.
+// If every variance is zero, the fraction is taken of 1.0 instead.
+pub(crate) fn variance_epsilon<'a>(
+    moments: impl Iterator<Item = &'a Moments>,
+    variance_smoothing: f64,
+) -> f64 {
+    let max_variance = moments
+        .map(Moments::variance)
+        .reduce(f64::max)
+        .filter(|x| *x > 0.0)
+        .unwrap_or(1.0);
+    variance_smoothing * max_variance
+}
@@ -0,0 +1,117 @@
+// Parallel training and classification, using rayon.
+//
+// Training is parallelised by sharding the data, counting each shard into a separate
+// Learner, and merging the Learners. This gives exactly the same counts as sequential
+// training, because the counts are sums.
+use crate::fixedclassifier::FixedClassifier;
+use crate::fixedlearner::FixedLearner;
+use crate::results::Results;
+use crate::streamclassifier::StreamClassifier;
+use crate::streamlearner::StreamLearner;
+use num_traits::Float;
+use rayon::prelude::*;
+use std::fmt::Debug;
+use std::hash::{BuildHasher, Hash};
+use std::ops::Add;
+
+impl<D, H, const DS: usize, S> FixedLearner<D, H, DS, S>
+where
+    D: Clone + Debug + Eq + Hash + Send + Sync,
+    H: Clone + Debug + Eq + Hash + Send + Sync,
+    S: BuildHasher + Default + Send + Sync,
+{
+    /// Update the Learner with multiple instances of training data for a single hypothesis, in
+    /// parallel.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - a slice of arrays that each represent a single instance of training data
+    /// * `hypothesis` - the single target hypothesis/label/category/classification for the data
+    ///
+    pub fn par_update_batch(&mut self, data: &[[D; DS]], hypothesis: H) -> &mut Self {
+        let learner = data
+            .par_iter()
+            .fold(Self::with_default_hasher, |mut learner, item| {
+                learner.update(item, hypothesis.clone());
+                learner
+            })
+            .reduce(Self::with_default_hasher, Add::add);
+        self.merge(&learner)
+    }
+}
+
+impl<D, H, S> StreamLearner<D, H, S>
+where
+    D: Clone + Debug + Eq + Hash + Send + Sync,
+    H: Clone + Debug + Eq + Hash + Send + Sync,
+    S: BuildHasher + Default + Send + Sync,
+{
+    /// Update the Learner with multiple streams of data for a single hypothesis, in parallel.
+    ///
+    /// # Arguments
+    ///
+    /// * `streams` - a slice of streams of training data, such as documents
+    /// * `hypothesis` - the single target hypothesis/label/category/classification for the data
+    ///
+    pub fn par_update_batch<T: AsRef<[D]> + Sync>(
+        &mut self,
+        streams: &[T],
+        hypothesis: H,
+    ) -> &mut Self {
+        let learner = streams
+            .par_iter()
+            .fold(Self::with_default_hasher, |mut learner, stream| {
+                learner.update(&mut stream.as_ref().iter().cloned(), hypothesis.clone());
+                learner
+            })
+            .reduce(Self::with_default_hasher, Add::add);
+        self.merge(&learner)
+    }
+}
+
+impl<D, H, const DS: usize, F, S> FixedClassifier<D, H, DS, F, S>
+where
+    D: Clone + Eq + Hash + Send + Sync,
+    H: Clone + Eq + Hash + Send + Sync,
+    F: Float + Send + Sync,
+    S: BuildHasher + Default + Send + Sync,
+{
+    /// Classify multiple unknown inputs in parallel.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - a slice of arrays of input data to classify
+    ///
+    /// # Return Value
+    ///
+    /// * `Vec<Results>` - the results for each input, in the same order as the inputs
+    ///
+    pub fn par_classify_batch(&self, data: &[[D; DS]]) -> Vec<Results<H, F>> {
+        data.par_iter().map(|item| self.classify(item)).collect()
+    }
+}
+
+impl<D, H, F, S> StreamClassifier<D, H, F, S>
+where
+    D: Clone + Eq + Hash + Send + Sync,
+    H: Clone + Eq + Hash + Send + Sync,
+    F: Float + Send + Sync,
+    S: BuildHasher + Default + Send + Sync,
+{
+    /// Classify multiple unknown streams in parallel.
+    ///
+    /// # Arguments
+    ///
+    /// * `streams` - a slice of streams of data to classify, such as documents
+    ///
+    /// # Return Value
+    ///
+    /// * `Vec<Results>` - the results for each stream, in the same order as the streams
+    ///
+    pub fn par_classify_batch<T: AsRef<[D]> + Sync>(&self, streams: &[T]) -> Vec<Results<H, F>> {
+        streams
+            .par_iter()
+            .map(|stream| self.classify_borrowed(&mut stream.as_ref().iter()))
+            .collect()
+    }
+}
@@ -1,66 +1,118 @@
+use crate::precision::log2_epsilon;
 use crate::Results;
-use std::collections::HashMap;
+use num_traits::Float;
 use std::hash::Hash;
 
-/// Returns posterior probabilities given log prirors and likelihoods, grouped by hypotheses.
+/// Writes posterior probabilities given log priors and likelihoods arranged by hypothesis id
+/// into results, without allocating if results already holds every hypothesis.
 ///
 /// # Arguments
 ///
-/// * `log_priors`:
-/// * `log_likelihoods`:
-///
-/// returns: Results<H>
-pub(crate) fn posteriors<D: Copy + Eq + Hash, H: Copy + Eq + Hash>(
-    log_priors: &HashMap<H, f64>,
-    log_likelihoods: &HashMap<H, f64>,
-) -> Results<H> {
-    const LOG2_MANTISSA_F64: f64 = -(f64::MANTISSA_DIGITS as f64);
-
-    // Discard any values that would result in a sum that would underflow a double precision
-    // floating point representation when exponentiated.
-    //
-    // As there are |H| log probabilities added together, each one must be greater than the
-    // minimum representable value divided by |H| to guarantee that the sum does not underflow.
-    let threshold = LOG2_MANTISSA_F64 - (log_priors.len() as f64).log2();
-
-    // Max log probability.
-    let max = log_likelihoods
-        .iter()
-        .map(|(_, x)| *x)
-        .into_iter()
-        .reduce(f64::max)
-        .unwrap_or(0.0);
+/// * `hypotheses`: the hypothesis of each id
+/// * `log_priors`: the log prior of each id
+/// * `log_likelihoods`: the accumulated log likelihood of each id, which is overwritten with the
+///   log joint probability, or None if no data was scored, in which case every posterior is 0
+/// * `known`: the number of input values that were seen in training
+/// * `results`: the results to overwrite
+pub(crate) fn dense_posteriors<H: Clone + Eq + Hash, F: Float>(
+    hypotheses: &[H],
+    log_priors: &[F],
+    log_likelihoods: Option<&mut [F]>,
+    known: usize,
+    results: &mut Results<H, F>,
+) {
+    let log_joints = match log_likelihoods {
+        Some(log_likelihoods) => log_likelihoods,
+        None => {
+            let unscored = (F::neg_infinity(), F::zero());
+            results.update(
+                hypotheses,
+                std::iter::repeat(unscored),
+                F::neg_infinity(),
+                0,
+            );
+            return;
+        }
+    };
 
     // Multiply each accumulated likelihood of h by the prior of h.
-    let relative_probabilities: HashMap<H, f64> = if log_likelihoods.is_empty() {
-        HashMap::default()
-    } else {
-        log_likelihoods
+    for (x, log_prior) in log_joints.iter_mut().zip(log_priors) {
+        *x = *x + *log_prior;
+    }
+
+    // Normalise joint probabilities.
+    let normaliser = Normaliser::new(log_joints.iter().copied(), hypotheses.len());
+    results.update(
+        hypotheses,
+        log_joints
             .iter()
-            .map(|(h, log_likelihood)| (*h, log_likelihood + *log_priors.get(h).unwrap()))
-            .map(|(h, x)| (h, x - max))
-            .filter(|(_, x)| *x > threshold)
-            .collect()
-    };
+            .map(|log_joint| (*log_joint, normaliser.posterior(*log_joint))),
+        normaliser.log_normaliser(),
+        known,
+    );
+}
 
-    // Sum relative probabilities.
-    let sum: f64 = relative_probabilities
-        .iter()
-        .map(|(_, p)| (*p).exp2())
-        .sum();
+// The sum of a set of joint probabilities, relative to the largest, from which the posterior
+// probabilities are found without the joint probabilities themselves underflowing.
+struct Normaliser<F> {
+    max: F,
+    sum: F,
+    threshold: F,
+}
+
+impl<F: Float> Normaliser<F> {
+    fn new(log_joints: impl Iterator<Item = F> + Clone, count: usize) -> Self {
+        // Max log probability.
+        let max = log_joints
+            .clone()
+            .reduce(F::max)
+            .unwrap_or_else(F::neg_infinity);
+        let mut normaliser = Normaliser {
+            max,
+            sum: F::zero(),
+            threshold: underflow_threshold::<F>(count),
+        };
+
+        // Sum relative probabilities.
+        normaliser.sum = log_joints.fold(F::zero(), |sum, x| sum + normaliser.relative(x));
+        normaliser
+    }
 
-    // Normalise relative probabilities and add any missing hypotheses.
-    let posteriors: HashMap<H, f64> = log_priors
-        .iter()
-        .map(|(h, _)| {
-            (
-                *h,
-                relative_probabilities
-                    .get(h)
-                    .map_or(0.0, |x| x.exp2() / sum),
-            )
-        })
-        .collect();
+    // The joint probability relative to the largest, or 0 if it would underflow the sum (or
+    // cannot be compared with the largest because either is infinite).
+    fn relative(&self, log_joint: F) -> F {
+        let x = log_joint - self.max;
+        if x > self.threshold {
+            x.exp2()
+        } else {
+            F::zero()
+        }
+    }
+
+    fn posterior(&self, log_joint: F) -> F {
+        let relative = self.relative(log_joint);
+        if relative > F::zero() {
+            relative / self.sum
+        } else {
+            F::zero()
+        }
+    }
+
+    fn log_normaliser(&self) -> F {
+        if self.sum > F::zero() {
+            self.max + self.sum.log2()
+        } else {
+            self.max
+        }
+    }
+}
 
-    Results::new(posteriors)
+// Discard any values that would result in a sum that would underflow the floating point type F
+// when exponentiated.
+//
+// As there are |H| log probabilities added together, each one must be greater than the minimum
+// representable value divided by |H| to guarantee that the sum does not underflow.
+fn underflow_threshold<F: Float>(count: usize) -> F {
+    let count: F = F::from(count).unwrap();
+    log2_epsilon::<F>() - count.log2()
 }
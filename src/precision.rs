@@ -0,0 +1,53 @@
+// Conversion between floating point types.
+//
+// Learners count in f64, as weighted counts accumulate rounding error, but Classifiers and
+// Results are generic over the floating point type F of their probabilities, so that a model
+// can be stored in f32 at half the memory. Log probabilities are far from the limits of any
+// floating point type, so conversion between types cannot fail.
+use num_traits::Float;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+
+// Convert a value to another floating point type.
+pub(crate) fn cast<F: Float, G: Float>(x: F) -> G {
+    G::from(x).unwrap()
+}
+
+// log2 of the smallest probability that is still significant when added to a probability of 1,
+// which is minus the number of binary digits of precision in the mantissa of F.
+pub(crate) fn log2_epsilon<F: Float>() -> F {
+    // epsilon is 2^(1 - mantissa digits).
+    F::epsilon().log2() - F::one()
+}
+
+// Convert each value of a slice to another floating point type.
+pub(crate) fn cast_values<F: Float, G: Float>(values: &[F]) -> Vec<G> {
+    values.iter().map(|x| cast(*x)).collect()
+}
+
+// Convert each value of a map to another floating point type.
+pub(crate) fn cast_map<K, V, G, C, S>(map: &HashMap<K, V, S>, convert: C) -> HashMap<K, G, S>
+where
+    K: Clone + Eq + Hash,
+    C: Fn(&V) -> G,
+    S: BuildHasher + Default,
+{
+    map.iter().map(|(k, v)| (k.clone(), convert(v))).collect()
+}
+
+// Convert each likelihood in each row of a map to another floating point type.
+#[allow(clippy::type_complexity)]
+pub(crate) fn cast_rows<D, H, F, G, S>(
+    map: &HashMap<D, Vec<(H, F)>, S>,
+) -> HashMap<D, Vec<(H, G)>, S>
+where
+    D: Clone + Eq + Hash,
+    H: Clone,
+    F: Float,
+    G: Float,
+    S: BuildHasher + Default,
+{
+    cast_map(map, |row: &Vec<(H, F)>| {
+        row.iter().map(|(h, p)| (h.clone(), cast(*p))).collect()
+    })
+}
@@ -0,0 +1,109 @@
+// Quantization of log likelihoods.
+//
+// A QuantizedClassifier stores each log2 likelihood as a bucket index rather than an f64.
+// The buckets divide the range of log likelihoods in the model evenly, so each log likelihood
+// is recovered as:
+//
+//      log2(p(d|h)) ~= offset + step * bucket
+//
+// where offset is the smallest log likelihood and step is the width of a bucket. The error in
+// each log likelihood is at most step / 2.
+
+/// The type of the bucket index that stores each log likelihood of a `QuantizedClassifier`.
+///
+/// Implemented for `u8` (256 buckets) and `u16` (65536 buckets).
+pub trait Bucket: Copy + PartialEq {
+    /// The largest bucket index.
+    const MAX: u32;
+
+    /// Convert a bucket index, which is at most `MAX`, to this type.
+    fn from_index(index: u32) -> Self;
+
+    /// Convert this value to a bucket index.
+    fn index(self) -> u32;
+}
+
+macro_rules! impl_bucket {
+    ($($t:ty),*) => {
+        $(
+            impl Bucket for $t {
+                const MAX: u32 = <$t>::MAX as u32;
+
+                fn from_index(index: u32) -> Self {
+                    index as $t
+                }
+
+                fn index(self) -> u32 {
+                    self as u32
+                }
+            }
+        )*
+    };
+}
+
+impl_bucket!(u8, u16);
+
+// The mapping between log likelihoods and bucket indices for a model.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Scale {
+    offset: f64,
+    step: f64,
+}
+
+impl Scale {
+    // Divide the range of log likelihoods from min to max into the buckets of Q.
+    pub(crate) fn new<Q: Bucket>(min: f64, max: f64) -> Self {
+        Scale {
+            offset: min,
+            step: (max - min) / Q::MAX as f64,
+        }
+    }
+
+    pub(crate) fn quantize<Q: Bucket>(&self, log_likelihood: f64) -> Q {
+        if self.step == 0.0 {
+            return Q::from_index(0);
+        }
+        let index = ((log_likelihood - self.offset) / self.step).round();
+        Q::from_index(index.max(0.0).min(Q::MAX as f64) as u32)
+    }
+
+    pub(crate) fn dequantize<Q: Bucket>(&self, bucket: Q) -> f64 {
+        self.offset + self.step * bucket.index() as f64
+    }
+
+    // Whether every bucket dequantizes to a finite log likelihood, which is false for a scale
+    // deserialised from corrupt data.
+    #[cfg(feature = "serde")]
+    pub(crate) fn is_valid(&self) -> bool {
+        self.offset.is_finite() && self.step.is_finite() && self.step >= 0.0
+    }
+}
+
+/// A comparison of the classifications of a `QuantizedClassifier` and the full precision
+/// `StreamClassifier` it was made from, over a labelled test set.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuantizationReport {
+    /// The number of test instances.
+    pub samples: usize,
+    /// The fraction of test instances whose best hypothesis by the full precision Classifier is
+    /// the labelled hypothesis.
+    pub full_accuracy: f64,
+    /// The fraction of test instances whose best hypothesis by the quantized Classifier is the
+    /// labelled hypothesis.
+    pub quantized_accuracy: f64,
+    /// The fraction of test instances where both Classifiers have the same best hypothesis.
+    pub agreement: f64,
+    /// The largest difference in the posterior probability of any hypothesis between the two
+    /// Classifiers, over all test instances.
+    pub max_probability_error: f64,
+}
+
+impl QuantizationReport {
+    /// Return the change in accuracy caused by quantization, which is negative if the quantized
+    /// Classifier is less accurate.
+    pub fn accuracy_delta(&self) -> f64 {
+        self.quantized_accuracy - self.full_accuracy
+    }
+}
@@ -0,0 +1,450 @@
+use crate::error::Error;
+#[cfg(feature = "serde")]
+use crate::interning::{extern_values, hypothesis_table, intern_rows, intern_values};
+use crate::quantization::{Bucket, QuantizationReport, Scale};
+use crate::results::Results;
+use crate::scratch::Scratch;
+use crate::streamclassifier::StreamClassifier;
+use crate::unseen::UnseenPolicy;
+use num_traits::Float;
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::hash::{BuildHasher, Hash};
+
+/// A `StreamClassifier` whose log likelihoods are stored as 8 or 16 bit buckets, with a single
+/// scale for the whole model.
+///
+/// The Classifier is made with `StreamClassifier::quantize` or
+/// `StreamLearner::make_quantized_classifier`. Priors and the estimates for unseen values are
+/// kept at full precision, as there is only one of each per hypothesis.
+///
+/// The hypothesis ids and buckets of the likelihoods are stored in two parallel arrays, so each
+/// likelihood takes 5 bytes with `u8` buckets or 6 bytes with `u16` buckets, however large the
+/// hypothesis type is.
+#[derive(Clone, Debug)]
+pub struct QuantizedClassifier<D, H, Q = u16, S = RandomState>
+where
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    Q: Bucket,
+    S: BuildHasher,
+{
+    // each hypothesis, indexed by its id (see interning.rs)
+    hypotheses: Vec<H>,
+    // probability of each hypothesis, by id
+    log_priors: Vec<f64>,
+    // the start and end of the likelihoods of each D in ids and buckets
+    log_likelihoods: HashMap<D, (u32, u32), S>,
+    // the id of the H of each likelihood, with the likelihoods of each D stored contiguously
+    ids: Vec<u32>,
+    // bucket of P(D|H) of each likelihood, parallel to ids, see quantization.rs
+    buckets: Vec<Q>,
+    scale: Scale,
+    // probability P(D|H) assumed for values not seen with H during training, by id
+    log_unseen: Vec<f64>,
+    unseen_policy: UnseenPolicy,
+}
+
+impl<D, H, Q, S> PartialEq for QuantizedClassifier<D, H, Q, S>
+where
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    Q: Bucket,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        // The rows are stored in the iteration order of the map, so compare them by value.
+        let same_rows = self.log_likelihoods.len() == other.log_likelihoods.len()
+            && self.log_likelihoods.iter().all(|(d, span)| {
+                other.log_likelihoods.get(d).map_or(false, |other_span| {
+                    self.row(*span).eq(other.row(*other_span))
+                })
+            });
+        self.hypotheses == other.hypotheses
+            && self.log_priors == other.log_priors
+            && same_rows
+            && self.scale == other.scale
+            && self.log_unseen == other.log_unseen
+            && self.unseen_policy == other.unseen_policy
+    }
+}
+
+impl<D, H, Q, S> QuantizedClassifier<D, H, Q, S>
+where
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    Q: Bucket,
+    S: BuildHasher + Default,
+{
+    /// Create a new Classifier, quantizing the log likelihoods.
+    ///
+    /// # Arguments
+    ///
+    /// * `hypotheses` - each hypothesis, indexed by its id
+    /// * `log_priors` - probability of hypothesis, by id
+    /// * `log_likelihoods` - probability of data given hypothesis id
+    /// * `log_unseen` - probability of unseen data given hypothesis, by id
+    /// * `unseen_policy` - the policy applied to unseen values
+    ///
+    pub(crate) fn new(
+        hypotheses: Vec<H>,
+        log_priors: Vec<f64>,
+        log_likelihoods: &HashMap<D, Vec<(u32, f64)>, S>,
+        log_unseen: Vec<f64>,
+        unseen_policy: UnseenPolicy,
+    ) -> Self {
+        let (min, max) = log_likelihoods
+            .values()
+            .flatten()
+            .fold(None, |range: Option<(f64, f64)>, (_, p)| match range {
+                Some((min, max)) => Some((min.min(*p), max.max(*p))),
+                None => Some((*p, *p)),
+            })
+            .unwrap_or((0.0, 0.0));
+        let scale = Scale::new::<Q>(min, max);
+        let (log_likelihoods, ids, buckets) = flatten(log_likelihoods.iter().map(|(d, row)| {
+            let row = row.iter().map(move |(id, p)| (*id, scale.quantize(*p)));
+            (d.clone(), row)
+        }));
+        QuantizedClassifier {
+            hypotheses,
+            log_priors,
+            log_likelihoods,
+            ids,
+            buckets,
+            scale,
+            log_unseen,
+            unseen_policy,
+        }
+    }
+
+    /// Set how data values that a hypothesis did not see during training are scored.
+    ///
+    /// # Arguments
+    ///
+    /// * `unseen_policy` - the policy applied to unseen values
+    ///
+    pub fn with_unseen_policy(mut self, unseen_policy: UnseenPolicy) -> Self {
+        self.unseen_policy = unseen_policy;
+        self
+    }
+
+    /// Classify an unknown input.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - a stream of data to classify for a single hypothesis.
+    ///
+    /// # Return Value
+    ///
+    /// * `Results` type
+    ///
+    /// # Panics
+    ///
+    /// * if the policy is `UnseenPolicy::Error` and a value was not seen by any hypothesis, see
+    ///   `try_classify`.
+    ///
+    pub fn classify(&self, stream: &mut dyn Iterator<Item = D>) -> Results<H> {
+        self.try_classify(stream).unwrap()
+    }
+
+    /// Classify an unknown input, failing if the `UnseenPolicy` rejects a value.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - a stream of data to classify for a single hypothesis.
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(Results)` - the classification results.
+    /// * `Err(Error::UnseenValue)` - a value was not seen by any hypothesis during training.
+    ///
+    pub fn try_classify(&self, stream: &mut dyn Iterator<Item = D>) -> Result<Results<H>, Error> {
+        let mut results = Results::default();
+        self.score::<D, D>(&mut Scratch::new(), stream, &mut results)?;
+        Ok(results)
+    }
+
+    /// Classify an unknown input of borrowed values, for example `&str` values when the data
+    /// type is `String`, without converting each value to the data type.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - a stream of borrowed data to classify for a single hypothesis.
+    ///
+    /// # Return Value
+    ///
+    /// * `Results` type
+    ///
+    /// # Panics
+    ///
+    /// * if the policy is `UnseenPolicy::Error` and a value was not seen by any hypothesis, see
+    ///   `try_classify_borrowed`.
+    ///
+    pub fn classify_borrowed<'q, R>(&self, stream: &mut dyn Iterator<Item = &'q R>) -> Results<H>
+    where
+        D: Borrow<R>,
+        R: Eq + Hash + ?Sized + 'q,
+    {
+        self.try_classify_borrowed(stream).unwrap()
+    }
+
+    /// Classify an unknown input of borrowed values, failing if the `UnseenPolicy` rejects a
+    /// value.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - a stream of borrowed data to classify for a single hypothesis.
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(Results)` - the classification results.
+    /// * `Err(Error::UnseenValue)` - a value was not seen by any hypothesis during training.
+    ///
+    pub fn try_classify_borrowed<'q, R>(
+        &self,
+        stream: &mut dyn Iterator<Item = &'q R>,
+    ) -> Result<Results<H>, Error>
+    where
+        D: Borrow<R>,
+        R: Eq + Hash + ?Sized + 'q,
+    {
+        let mut results = Results::default();
+        self.score::<R, &R>(&mut Scratch::new(), stream, &mut results)?;
+        Ok(results)
+    }
+
+    /// Compare the classifications of this Classifier with those of the full precision
+    /// Classifier it was made from, over a labelled test set.
+    ///
+    /// # Arguments
+    ///
+    /// * `full` - the full precision Classifier
+    /// * `test` - streams of test data, each with its expected hypothesis
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(QuantizationReport)` - the comparison. Every rate is 0 if `test` is empty.
+    /// * `Err(Error::UnseenValue)` - a value was not seen by any hypothesis during training.
+    ///
+    pub fn report<T, F>(
+        &self,
+        full: &StreamClassifier<D, H, F, S>,
+        test: &[(T, H)],
+    ) -> Result<QuantizationReport, Error>
+    where
+        T: AsRef<[D]>,
+        F: Float,
+    {
+        let mut full_correct = 0;
+        let mut quantized_correct = 0;
+        let mut agreed = 0;
+        let mut max_probability_error: f64 = 0.0;
+        for (stream, hypothesis) in test {
+            let expected = full.try_classify_borrowed(&mut stream.as_ref().iter())?;
+            let actual = self.try_classify_borrowed(&mut stream.as_ref().iter())?;
+
+            let expected_best = expected.best().map(|(h, _)| h);
+            let actual_best = actual.best().map(|(h, _)| h);
+            full_correct += (expected_best.as_ref() == Some(hypothesis)) as usize;
+            quantized_correct += (actual_best.as_ref() == Some(hypothesis)) as usize;
+            agreed += (expected_best == actual_best) as usize;
+
+            let expected: HashMap<H, F> = expected.into_iter().collect();
+            for (h, p) in actual {
+                let q = expected.get(&h).map_or(0.0, |q| q.to_f64().unwrap());
+                max_probability_error = max_probability_error.max((p - q).abs());
+            }
+        }
+
+        let rate = |count: usize| {
+            if test.is_empty() {
+                0.0
+            } else {
+                count as f64 / test.len() as f64
+            }
+        };
+        Ok(QuantizationReport {
+            samples: test.len(),
+            full_accuracy: rate(full_correct),
+            quantized_accuracy: rate(quantized_correct),
+            agreement: rate(agreed),
+            max_probability_error,
+        })
+    }
+
+    // Classify a stream of values that can be borrowed in the same form as the data type.
+    fn score<R, T>(
+        &self,
+        scratch: &mut Scratch,
+        stream: &mut dyn Iterator<Item = T>,
+        results: &mut Results<H>,
+    ) -> Result<(), Error>
+    where
+        D: Borrow<R>,
+        T: Borrow<R>,
+        R: Eq + Hash + ?Sized,
+    {
+        // Accumulate product of likelihoods, by hypothesis id.
+        scratch.reset(self.hypotheses.len());
+        for (i, d) in stream.enumerate() {
+            let found = match self.log_likelihoods.get(d.borrow()) {
+                Some(found) => *found,
+                None => match self.unseen_policy {
+                    UnseenPolicy::Skip => continue,
+                    UnseenPolicy::Error => return Err(Error::UnseenValue { position: i }),
+                    _ => (0, 0),
+                },
+            };
+            scratch.add(
+                i,
+                self.row(found),
+                &self.log_unseen,
+                self.unseen_policy,
+                |bucket| self.scale.dequantize(bucket),
+            );
+        }
+
+        scratch.posteriors_into(&self.hypotheses, &self.log_priors, results);
+        Ok(())
+    }
+}
+
+impl<D, H, Q, S> QuantizedClassifier<D, H, Q, S>
+where
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    Q: Bucket,
+    S: BuildHasher,
+{
+    // The (hypothesis id, bucket) pairs of a row, given its start and end.
+    fn row(&self, (start, end): (u32, u32)) -> impl Iterator<Item = (u32, Q)> + '_ {
+        let range = start as usize..end as usize;
+        self.ids[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.buckets[range].iter().copied())
+    }
+}
+
+// Store rows of (hypothesis id, bucket) pairs contiguously in parallel arrays of ids and
+// buckets, with the start and end of each row.
+#[allow(clippy::type_complexity)]
+fn flatten<D, Q, R, S>(
+    rows: impl Iterator<Item = (D, R)>,
+) -> (HashMap<D, (u32, u32), S>, Vec<u32>, Vec<Q>)
+where
+    D: Eq + Hash,
+    R: IntoIterator<Item = (u32, Q)>,
+    S: BuildHasher + Default,
+{
+    let mut spans = HashMap::default();
+    let mut ids = Vec::new();
+    let mut buckets = Vec::new();
+    for (d, row) in rows {
+        let start = ids.len();
+        for (id, bucket) in row {
+            ids.push(id);
+            buckets.push(bucket);
+        }
+        spans.insert(d, (offset(start), offset(ids.len())));
+    }
+    ids.shrink_to_fit();
+    buckets.shrink_to_fit();
+    (spans, ids, buckets)
+}
+
+// Offsets into ids and buckets are stored as u32, the same as hypothesis ids.
+fn offset(index: usize) -> u32 {
+    index
+        .try_into()
+        .expect("a QuantizedClassifier holds at most u32::MAX likelihoods")
+}
+
+// The serialized form of a QuantizedClassifier, which refers to hypotheses by value.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(bound(
+    serialize = "D: serde::Serialize, H: serde::Serialize, Q: serde::Serialize",
+    deserialize = "D: serde::Deserialize<'de>, H: serde::Deserialize<'de>, \
+                   Q: serde::Deserialize<'de>, S: BuildHasher + Default"
+))]
+struct QuantizedClassifierEntries<D, H, Q, S>
+where
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    S: BuildHasher,
+{
+    #[serde(with = "crate::serialization::entries")]
+    log_priors: HashMap<H, f64, S>,
+    #[serde(with = "crate::serialization::entries")]
+    log_likelihoods: HashMap<D, Vec<(H, Q)>, S>,
+    scale: Scale,
+    #[serde(with = "crate::serialization::entries")]
+    log_unseen: HashMap<H, f64, S>,
+    unseen_policy: UnseenPolicy,
+}
+
+#[cfg(feature = "serde")]
+impl<D, H, Q, S> serde::Serialize for QuantizedClassifier<D, H, Q, S>
+where
+    D: Clone + Eq + Hash + serde::Serialize,
+    H: Clone + Eq + Hash + serde::Serialize,
+    Q: Bucket + serde::Serialize,
+    S: BuildHasher + Default,
+{
+    fn serialize<T: serde::Serializer>(&self, serializer: T) -> Result<T::Ok, T::Error> {
+        QuantizedClassifierEntries {
+            log_priors: extern_values::<_, _, S>(&self.log_priors, &self.hypotheses),
+            log_likelihoods: self
+                .log_likelihoods
+                .iter()
+                .map(|(d, span)| {
+                    let row = self
+                        .row(*span)
+                        .map(|(id, bucket)| (self.hypotheses[id as usize].clone(), bucket))
+                        .collect();
+                    (d.clone(), row)
+                })
+                .collect(),
+            scale: self.scale,
+            log_unseen: extern_values(&self.log_unseen, &self.hypotheses),
+            unseen_policy: self.unseen_policy,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, D, H, Q, S> serde::Deserialize<'de> for QuantizedClassifier<D, H, Q, S>
+where
+    D: Clone + Eq + Hash + serde::Deserialize<'de>,
+    H: Clone + Eq + Hash + serde::Deserialize<'de>,
+    Q: Bucket + serde::Deserialize<'de>,
+    S: BuildHasher + Default,
+{
+    fn deserialize<T: serde::Deserializer<'de>>(deserializer: T) -> Result<Self, T::Error> {
+        let entries = QuantizedClassifierEntries::<D, H, Q, S>::deserialize(deserializer)?;
+        let (hypotheses, ids) = hypothesis_table(&entries.log_priors);
+        let rows = intern_rows(entries.log_likelihoods, &ids)
+            .ok_or_else(|| serde::de::Error::custom(Error::InconsistentModel))?;
+        let log_unseen = intern_values(&entries.log_unseen, &hypotheses)
+            .ok_or_else(|| serde::de::Error::custom(Error::InconsistentModel))?;
+        if !entries.scale.is_valid() {
+            return Err(serde::de::Error::custom(Error::InconsistentModel));
+        }
+        let (log_likelihoods, ids, buckets) = flatten(rows.into_iter());
+        Ok(QuantizedClassifier {
+            log_priors: intern_values(&entries.log_priors, &hypotheses).unwrap(),
+            hypotheses,
+            log_likelihoods,
+            ids,
+            buckets,
+            scale: entries.scale,
+            log_unseen,
+            unseen_policy: entries.unseen_policy,
+        })
+    }
+}
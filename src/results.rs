@@ -1,53 +1,425 @@
+use crate::hashing::stable_hash;
+use num_traits::Float;
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::hash::Hash;
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct Results<H>
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "H: serde::Serialize, F: serde::Serialize",
+        deserialize = "H: serde::Deserialize<'de>, F: serde::Deserialize<'de>"
+    ))
+)]
+pub struct Results<H, F = f64>
 where
-    H: Copy + Eq + Hash,
+    H: Clone + Eq + Hash,
+    F: Float,
 {
-    values: HashMap<H, f64>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::entries"))]
+    values: HashMap<H, F>,
+    // log2 of the prior of each hypothesis multiplied by the likelihoods of the input, which is
+    // -inf if no input was scored
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::entries"))]
+    log_joints: HashMap<H, F>,
+    // log2 of the sum of the joint probabilities of every hypothesis
+    log_normaliser: F,
+    // the number of input values that were seen in training
+    known: usize,
 }
 
-impl<H: Copy + Eq + Hash> Results<H> {
+impl<H: Clone + Eq + Hash, F: Float> Results<H, F> {
     /// Create a struct to hold classification results.
     ///
     /// # Arguments
     ///
     /// * `values` - posterior probability of each hypothesis
+    /// * `log_joints` - log2 joint probability of each hypothesis and the input
+    /// * `log_normaliser` - log2 of the sum of the joint probabilities
+    /// * `known` - the number of input values that were seen in training
     ///
-    pub(crate) fn new(values: HashMap<H, f64>) -> Self {
-        Self { values }
+    pub(crate) fn new(
+        values: HashMap<H, F>,
+        log_joints: HashMap<H, F>,
+        log_normaliser: F,
+        known: usize,
+    ) -> Self {
+        Self {
+            values,
+            log_joints,
+            log_normaliser,
+            known,
+        }
+    }
+
+    // Overwrite the log joint probability and posterior probability of each hypothesis. The maps
+    // are only rebuilt if they do not already hold exactly these hypotheses, so updating the
+    // Results of the same Classifier again does not allocate.
+    pub(crate) fn update(
+        &mut self,
+        hypotheses: &[H],
+        scores: impl Iterator<Item = (F, F)>,
+        log_normaliser: F,
+        known: usize,
+    ) {
+        if self.values.len() != hypotheses.len()
+            || !hypotheses.iter().all(|h| self.values.contains_key(h))
+        {
+            self.values.clear();
+            self.log_joints.clear();
+        }
+        for (h, (log_joint, p)) in hypotheses.iter().zip(scores) {
+            match self.values.get_mut(h) {
+                Some(value) => *value = p,
+                None => {
+                    self.values.insert(h.clone(), p);
+                }
+            }
+            match self.log_joints.get_mut(h) {
+                Some(value) => *value = log_joint,
+                None => {
+                    self.log_joints.insert(h.clone(), log_joint);
+                }
+            }
+        }
+        self.log_normaliser = log_normaliser;
+        self.known = known;
     }
 
     /// Return the hypothesis with the highest posterior probability.
     ///
+    /// NaN probabilities are lower than any number. Hypotheses with the same probability, for
+    /// example two that have both underflowed to 0, are compared by their log joint
+    /// probabilities, and remaining ties are broken by a fixed hash of the hypotheses, so the same
+    /// hypothesis is returned in every run regardless of the hasher used by the Classifier. See
+    /// `best_by` to break ties in another way.
+    ///
+    /// # Return Value
+    ///
+    /// * `Option::None` - no results.
+    /// * `Option::Some((H, F))` - the hypothesis and its posterior probability.
+    ///
+    pub fn best(&self) -> Option<(H, F)> {
+        // Prefer the hypothesis with the lower hash.
+        self.best_by(|lhs, rhs| stable_hash(lhs).cmp(&stable_hash(rhs)))
+    }
+
+    /// Return the hypothesis with the highest posterior probability, breaking ties with the
+    /// given ordering of hypotheses.
+    ///
+    /// NaN probabilities are lower than any number, and hypotheses with the same probability are
+    /// compared by their log joint probabilities, as for `best`. Of the hypotheses that are still
+    /// tied, the first in the order of `tie_break` is returned. For example,
+    /// `best_by(Ord::cmp)` prefers the smallest hypothesis, and given the prior of each
+    /// hypothesis in a map, `best_by(|lhs, rhs| priors[rhs].partial_cmp(&priors[lhs]).unwrap())`
+    /// prefers the hypothesis with the highest prior.
+    ///
+    /// # Arguments
+    ///
+    /// * `tie_break` - a total order of the hypotheses, earliest first
+    ///
     /// # Return Value
     ///
-    /// * `Option::Empty() - no results.
-    /// * `Option::Some((H, f64))` - the hypothesis and its posterior probability.
+    /// * `Option::None` - no results.
+    /// * `Option::Some((H, F))` - the hypothesis and its posterior probability.
     ///
-    pub fn best(&self) -> Option<(H, f64)> {
+    pub fn best_by(&self, mut tie_break: impl FnMut(&H, &H) -> Ordering) -> Option<(H, F)> {
         self.values
             .iter()
-            .max_by(|&lhs, &rhs| {
-                if lhs.1 < rhs.1 {
-                    Ordering::Less
+            .map(|(h, p)| (h, *p, self.log_joint_or_nan(h)))
+            .min_by(|lhs, rhs| {
+                descending(lhs.1, rhs.1)
+                    .then(descending(lhs.2, rhs.2))
+                    .then_with(|| tie_break(lhs.0, rhs.0))
+            })
+            .map(|(h, p, _)| (h.clone(), p))
+    }
+
+    /// Return the hypothesis with the highest posterior probability, only if no other
+    /// hypothesis is as probable.
+    ///
+    /// # Return Value
+    ///
+    /// * `Option::None` - no results, the highest probability is NaN, or the two most probable
+    ///   hypotheses are tied, with the same probability and log joint probability.
+    /// * `Option::Some((H, F))` - the hypothesis and its posterior probability.
+    ///
+    pub fn best_unique(&self) -> Option<(H, F)> {
+        let mut top = self.top_k(2).into_iter();
+        let best = top.next().filter(|(_, p)| !p.is_nan());
+        match (best, top.next()) {
+            (Some((h, p)), Some((g, q)))
+                if p == q && self.log_joint_or_nan(&h) == self.log_joint_or_nan(&g) =>
+            {
+                None
+            }
+            (best, _) => best,
+        }
+    }
+
+    /// Return the posterior probability of a hypothesis.
+    ///
+    /// # Arguments
+    ///
+    /// * `hypothesis` - the hypothesis to look up
+    ///
+    /// # Return Value
+    ///
+    /// * `Option::None` - the hypothesis is not in the results.
+    /// * `Option::Some(F)` - the posterior probability of the hypothesis.
+    ///
+    pub fn get(&self, hypothesis: &H) -> Option<F> {
+        self.values.get(hypothesis).copied()
+    }
+
+    /// Return log2 of the joint probability of a hypothesis and the input, p(h) * p(d|h) for
+    /// each value d, which is not normalised and so does not underflow to 0 like the posterior
+    /// probability.
+    ///
+    /// # Arguments
+    ///
+    /// * `hypothesis` - the hypothesis to look up
+    ///
+    /// # Return Value
+    ///
+    /// * `Option::None` - the hypothesis is not in the results.
+    /// * `Option::Some(F)` - the log2 joint probability, or -inf if no input was scored.
+    ///
+    pub fn log_joint(&self, hypothesis: &H) -> Option<F> {
+        self.log_joints.get(hypothesis).copied()
+    }
+
+    /// Return log2 of the sum of the joint probabilities of every hypothesis, which normalises
+    /// the joint probabilities into posterior probabilities.
+    ///
+    /// # Return Value
+    ///
+    /// * `F` - the log2 normaliser, or -inf if there are no results or no input was scored.
+    ///
+    pub fn log_normaliser(&self) -> F {
+        self.log_normaliser
+    }
+
+    /// Return log2 of the posterior probability of a hypothesis, which is its log joint
+    /// probability less the log normaliser, so it remains finite where the posterior
+    /// probability underflows to 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `hypothesis` - the hypothesis to look up
+    ///
+    /// # Return Value
+    ///
+    /// * `Option::None` - the hypothesis is not in the results.
+    /// * `Option::Some(F)` - the log2 posterior probability, or -inf if no input was scored.
+    ///
+    pub fn log_posterior(&self, hypothesis: &H) -> Option<F> {
+        self.log_joint(hypothesis).map(|log_joint| {
+            if log_joint == F::neg_infinity() {
+                log_joint
+            } else {
+                log_joint - self.log_normaliser
+            }
+        })
+    }
+
+    /// Return the number of values in the input that were seen in training by at least one
+    /// hypothesis, so that a classification based on little or no evidence can be recognised.
+    /// Values skipped by `UnseenPolicy::Skip` or scored with the unseen estimate are not counted,
+    /// while each value of continuous data is.
+    ///
+    /// # Return Value
+    ///
+    /// * `usize` - the number of known values, or 0 for results collected from probabilities.
+    ///
+    pub fn known(&self) -> usize {
+        self.known
+    }
+
+    /// Iterate over each hypothesis and its log2 joint probability, in no particular order.
+    pub fn log_joints(&self) -> std::collections::hash_map::Iter<'_, H, F> {
+        self.log_joints.iter()
+    }
+
+    /// Return the number of hypotheses in the results.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Return true if there are no hypotheses in the results.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Iterate over each hypothesis and its posterior probability, in no particular order,
+    /// without consuming the results.
+    pub fn iter(&self) -> std::collections::hash_map::Iter<'_, H, F> {
+        self.values.iter()
+    }
+
+    /// Return every hypothesis and its posterior probability, from most to least probable.
+    ///
+    /// Ties are broken as for `best`, and NaN probabilities are ranked last, so the order is the
+    /// same in every run.
+    ///
+    /// # Return Value
+    ///
+    /// * `Vec<(H, F)>` - the hypotheses in descending order of probability.
+    ///
+    pub fn ranked(&self) -> Vec<(H, F)> {
+        self.top_k(self.values.len())
+    }
+
+    /// Return the `k` most probable hypotheses and their posterior probabilities, in the same
+    /// order as `ranked`.
+    ///
+    /// # Arguments
+    ///
+    /// * `k` - the number of hypotheses to return
+    ///
+    /// # Return Value
+    ///
+    /// * `Vec<(H, F)>` - at most `k` hypotheses in descending order of probability.
+    ///
+    pub fn top_k(&self, k: usize) -> Vec<(H, F)> {
+        if k == 0 {
+            return Vec::new();
+        }
+        let mut ranked: Vec<(u64, &H, F, F)> = self
+            .values
+            .iter()
+            .map(|(h, p)| (stable_hash(h), h, *p, self.log_joint_or_nan(h)))
+            .collect();
+        if k < ranked.len() {
+            ranked.select_nth_unstable_by(k - 1, rank);
+            ranked.truncate(k);
+        }
+        ranked.sort_unstable_by(rank);
+        ranked
+            .into_iter()
+            .map(|(_, h, p, _)| (h.clone(), p))
+            .collect()
+    }
+
+    /// Return the entropy of the posterior probabilities in bits, which is 0 when one hypothesis
+    /// is certain and log2 of the number of hypotheses when all are equally probable.
+    ///
+    /// # Return Value
+    ///
+    /// * `F` - the entropy, or 0 if there are no results. NaN probabilities are ignored.
+    ///
+    pub fn entropy(&self) -> F {
+        self.values
+            .values()
+            .filter(|p| **p > F::zero())
+            .fold(F::zero(), |entropy, p| entropy - *p * p.log2())
+    }
+
+    /// Return the difference between the posterior probabilities of the two most probable
+    /// hypotheses, in the order of `ranked`.
+    ///
+    /// # Return Value
+    ///
+    /// * `F` - the margin, which is the probability of the best hypothesis if there is only one,
+    ///   or 0 if there are no results.
+    ///
+    pub fn margin(&self) -> F {
+        match self.top_k(2)[..] {
+            [] => F::zero(),
+            [(_, p)] => p,
+            [(_, p), (_, q)] => p - q,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Return the ratio of the posterior probabilities of the two most probable hypotheses, in
+    /// the order of `ranked`.
+    ///
+    /// The ratio is found from the log joint probabilities, so it is exact even when the
+    /// posterior probability of the second hypothesis has underflowed to 0.
+    ///
+    /// # Return Value
+    ///
+    /// * `F` - the ratio, which is at least 1. It is infinite if there is only one hypothesis,
+    ///   and 1 if there are no results or no input was scored.
+    ///
+    pub fn confidence_ratio(&self) -> F {
+        match self.top_k(2)[..] {
+            [] => F::one(),
+            [_] => F::infinity(),
+            [(ref best, _), (ref second, _)] => {
+                let best = self.log_joint_or_nan(best);
+                let second = self.log_joint_or_nan(second);
+                if best == F::neg_infinity() && second == F::neg_infinity() {
+                    F::one()
                 } else {
-                    Ordering::Greater
+                    (best - second).exp2()
                 }
-            })
-            .map(|o| (*o.0, *o.1))
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn log_joint_or_nan(&self, hypothesis: &H) -> F {
+        self.log_joint(hypothesis).unwrap_or_else(F::nan)
     }
 }
 
-impl<H: Copy + Eq + Hash> IntoIterator for Results<H> {
-    type Item = (H, f64);
+// Order probabilities from highest to lowest, with NaN after any number, so that NaN never
+// wins and every pair of probabilities is ordered.
+fn descending<F: Float>(lhs: F, rhs: F) -> Ordering {
+    match (lhs.is_nan(), rhs.is_nan()) {
+        (false, false) => rhs.partial_cmp(&lhs).unwrap(),
+        (lhs_nan, rhs_nan) => lhs_nan.cmp(&rhs_nan),
+    }
+}
 
-    type IntoIter = std::collections::hash_map::IntoIter<H, f64>;
+// Order hypotheses, with their stable hashes, probabilities and log joint probabilities, from
+// most to least probable, breaking ties by preferring the higher log joint probability and then
+// the lower hash.
+fn rank<H, F: Float>(lhs: &(u64, &H, F, F), rhs: &(u64, &H, F, F)) -> Ordering {
+    descending(lhs.2, rhs.2)
+        .then(descending(lhs.3, rhs.3))
+        .then(lhs.0.cmp(&rhs.0))
+}
+
+impl<H: Clone + Eq + Hash, F: Float> Default for Results<H, F> {
+    /// Create empty results, for use with `classify_into`.
+    fn default() -> Self {
+        Self::new(HashMap::new(), HashMap::new(), F::neg_infinity(), 0)
+    }
+}
+
+impl<H: Clone + Eq + Hash, F: Float> FromIterator<(H, F)> for Results<H, F> {
+    /// Collect results from the posterior probability of each hypothesis, for example to combine
+    /// the results of several Classifiers. The probabilities are taken to be normalised, so the
+    /// log joint probability of each hypothesis is log2 of its probability and the log
+    /// normaliser is 0. No input values are known.
+    fn from_iter<T: IntoIterator<Item = (H, F)>>(iter: T) -> Self {
+        let values: HashMap<H, F> = iter.into_iter().collect();
+        let log_joints = values.iter().map(|(h, p)| (h.clone(), p.log2())).collect();
+        Self::new(values, log_joints, F::zero(), 0)
+    }
+}
+
+impl<H: Clone + Eq + Hash, F: Float> IntoIterator for Results<H, F> {
+    type Item = (H, F);
+
+    type IntoIter = std::collections::hash_map::IntoIter<H, F>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.values.into_iter()
     }
 }
+
+impl<'a, H: Clone + Eq + Hash, F: Float> IntoIterator for &'a Results<H, F> {
+    type Item = (&'a H, &'a F);
+
+    type IntoIter = std::collections::hash_map::Iter<'a, H, F>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.iter()
+    }
+}
@@ -0,0 +1,103 @@
+use crate::posteriors::dense_posteriors;
+use crate::results::Results;
+use crate::unseen::UnseenPolicy;
+use num_traits::Float;
+use std::hash::Hash;
+
+/// Reusable working space for classification.
+///
+/// `classify_into` accumulates the likelihood of each hypothesis in a `Scratch` and writes the
+/// posteriors into an existing `Results`, so reusing both across calls to the same Classifier
+/// means that classification performs no heap allocation once the buffers have grown to the
+/// number of hypotheses. A `Scratch` can be used with any Classifier with the same floating
+/// point type, but not by two calls at once.
+#[derive(Clone, Debug, Default)]
+pub struct Scratch<F = f64> {
+    // the log likelihoods of each hypothesis, indexed by hypothesis id (see interning.rs)
+    log_likelihoods: Vec<F>,
+    // the last position at which each hypothesis was found in a row, so that the hypotheses
+    // not found at a position can be scored as unseen without building a set of them
+    found_at: Vec<usize>,
+    scored: bool,
+    // the number of values added that were seen in training
+    known: usize,
+}
+
+impl<F: Float> Scratch<F> {
+    /// Create empty working space, which grows to the number of hypotheses when first used.
+    ///
+    /// # Return Value
+    ///
+    /// * `Scratch` type
+    ///
+    pub fn new() -> Self {
+        Scratch {
+            log_likelihoods: Vec::new(),
+            found_at: Vec::new(),
+            scored: false,
+            known: 0,
+        }
+    }
+
+    // Clear the accumulated likelihoods of the given number of hypotheses.
+    pub(crate) fn reset(&mut self, count: usize) {
+        self.log_likelihoods.clear();
+        self.log_likelihoods.resize(count, F::zero());
+        self.found_at.clear();
+        self.found_at.resize(count, usize::MAX);
+        self.scored = false;
+        self.known = 0;
+    }
+
+    // Start the accumulated likelihood of each hypothesis from a given log2 probability, which
+    // is scored even if no value is added.
+    pub(crate) fn reset_to(&mut self, log_likelihoods: &[F]) {
+        self.reset(log_likelihoods.len());
+        self.log_likelihoods.copy_from_slice(log_likelihoods);
+        self.scored = true;
+    }
+
+    // Add log2(p(d|h)) for the value d at a position to the accumulated likelihood of each h:
+    // from the row of d if h was seen with d, otherwise the estimate for unseen values.
+    pub(crate) fn add<V>(
+        &mut self,
+        position: usize,
+        row: impl IntoIterator<Item = (u32, V)>,
+        log_unseen: &[F],
+        unseen_policy: UnseenPolicy,
+        log_likelihood: impl Fn(V) -> F,
+    ) {
+        self.scored = true;
+        let mut seen = false;
+        for (id, v) in row {
+            let id = id as usize;
+            self.log_likelihoods[id] = self.log_likelihoods[id] + log_likelihood(v);
+            self.found_at[id] = position;
+            seen = true;
+        }
+        if seen {
+            self.known += 1;
+        }
+        for (id, log_unseen) in log_unseen.iter().enumerate() {
+            if self.found_at[id] != position {
+                self.log_likelihoods[id] =
+                    self.log_likelihoods[id] + unseen_policy.log2_unseen(*log_unseen);
+            }
+        }
+    }
+
+    // Normalise the accumulated likelihoods into posterior probabilities, in place.
+    pub(crate) fn posteriors_into<H: Clone + Eq + Hash>(
+        &mut self,
+        hypotheses: &[H],
+        log_priors: &[F],
+        results: &mut Results<H, F>,
+    ) {
+        let log_likelihoods = if self.scored {
+            Some(&mut self.log_likelihoods[..])
+        } else {
+            None
+        };
+        dense_posteriors(hypotheses, log_priors, log_likelihoods, self.known, results);
+    }
+}
@@ -0,0 +1,158 @@
+// Serde support for the containers used inside Learners and Classifiers.
+//
+// Maps are serialized as sequences of (key, value) entries rather than as maps, because
+// many formats (JSON, for example) only allow string keys, while the keys here are
+// arbitrary data values, hypotheses or (data, hypothesis) pairs.
+//
+// Entries are written in order of the stable hash of their keys rather than in the iteration
+// order of the map, so that the same model is always serialized in the same way.
+//
+// Learners trust their counts, so counts are checked when they are deserialized: a negative,
+// infinite or NaN count would make every probability estimated from it NaN.
+//
+// Serde does not implement Serialize and Deserialize for arrays with a const generic
+// length, so arrays are serialized as sequences and their length is checked when they
+// are deserialized.
+use crate::hashing::stable_hash;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::hash::{BuildHasher, Hash};
+
+struct Entries<'a, K, V, B>(&'a HashMap<K, V, B>);
+
+impl<K: Hash + Serialize, V: Serialize, B> Serialize for Entries<'_, K, V, B> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut entries: Vec<(&K, &V)> = self.0.iter().collect();
+        entries.sort_by_cached_key(|(k, _)| stable_hash(*k));
+        serializer.collect_seq(entries)
+    }
+}
+
+// Serialize a HashMap as a sequence of (key, value) entries.
+pub(crate) mod entries {
+    use super::*;
+
+    pub(crate) fn serialize<K: Hash + Serialize, V: Serialize, B, S: Serializer>(
+        map: &HashMap<K, V, B>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        Entries(map).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, K, V, B, De>(
+        deserializer: De,
+    ) -> Result<HashMap<K, V, B>, De::Error>
+    where
+        K: Deserialize<'de> + Eq + Hash,
+        V: Deserialize<'de>,
+        B: BuildHasher + Default,
+        De: Deserializer<'de>,
+    {
+        let entries: Vec<(K, V)> = Vec::deserialize(deserializer)?;
+        Ok(entries.into_iter().collect())
+    }
+}
+
+// Serialize an array of HashMaps as a sequence of sequences of (key, value) entries.
+pub(crate) mod array_entries {
+    use super::*;
+
+    pub(crate) fn serialize<K: Hash + Serialize, V: Serialize, B, S: Serializer, const N: usize>(
+        array: &[HashMap<K, V, B>; N],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(array.iter().map(Entries))
+    }
+
+    pub(crate) fn deserialize<'de, K, V, B, De, const N: usize>(
+        deserializer: De,
+    ) -> Result<[HashMap<K, V, B>; N], De::Error>
+    where
+        K: Deserialize<'de> + Eq + Hash,
+        V: Deserialize<'de>,
+        B: BuildHasher + Default,
+        De: Deserializer<'de>,
+    {
+        let array: Vec<Vec<(K, V)>> = Vec::deserialize(deserializer)?;
+        let length = array.len();
+        array
+            .into_iter()
+            .map(|entries| entries.into_iter().collect())
+            .collect::<Vec<HashMap<K, V, B>>>()
+            .try_into()
+            .map_err(|_| De::Error::invalid_length(length, &format!("{} positions", N).as_str()))
+    }
+}
+
+// Check that a deserialized count is non-negative and finite.
+fn check_count<E: Error>(count: f64) -> Result<f64, E> {
+    if count.is_finite() && count >= 0.0 {
+        Ok(count)
+    } else {
+        Err(E::custom(format!(
+            "count must be non-negative and finite, not {}",
+            count
+        )))
+    }
+}
+
+// Serialize a count as a number.
+pub(crate) mod count {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(count: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+        count.serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, De: Deserializer<'de>>(
+        deserializer: De,
+    ) -> Result<f64, De::Error> {
+        check_count(f64::deserialize(deserializer)?)
+    }
+}
+
+// Serialize a HashMap of counts as a sequence of (key, count) entries.
+pub(crate) mod counts {
+    use super::*;
+
+    pub(crate) use super::entries::serialize;
+
+    pub(crate) fn deserialize<'de, K, B, De>(
+        deserializer: De,
+    ) -> Result<HashMap<K, f64, B>, De::Error>
+    where
+        K: Deserialize<'de> + Eq + Hash,
+        B: BuildHasher + Default,
+        De: Deserializer<'de>,
+    {
+        let counts: HashMap<K, f64, B> = entries::deserialize(deserializer)?;
+        for count in counts.values() {
+            check_count(*count)?;
+        }
+        Ok(counts)
+    }
+}
+
+// Serialize an array of HashMaps of counts as a sequence of sequences of (key, count) entries.
+pub(crate) mod array_counts {
+    use super::*;
+
+    pub(crate) use super::array_entries::serialize;
+
+    pub(crate) fn deserialize<'de, K, B, De, const N: usize>(
+        deserializer: De,
+    ) -> Result<[HashMap<K, f64, B>; N], De::Error>
+    where
+        K: Deserialize<'de> + Eq + Hash,
+        B: BuildHasher + Default,
+        De: Deserializer<'de>,
+    {
+        let array: [HashMap<K, f64, B>; N] = array_entries::deserialize(deserializer)?;
+        for count in array.iter().flat_map(HashMap::values) {
+            check_count(*count)?;
+        }
+        Ok(array)
+    }
+}
@@ -0,0 +1,134 @@
+use crate::error::Error;
+
+/// Placeholder estimate of log2(p(d|h)) used for values that were not seen with a hypothesis
+/// during training, when no smoothing is applied.
+///
+/// This is the number of binary digits of precision in a double precision floating point
+/// mantissa, so the placeholder is the smallest probability that is still significant when
+/// added to a probability of 1.
+pub(crate) const LOG2_PLACEHOLDER_PROBABILITY: f64 = -(f64::MANTISSA_DIGITS as f64);
+
+/// Additive smoothing applied when estimating p(d|h) from training counts.
+///
+/// Smoothing adds a pseudo-count alpha to the count of every value, including values that were
+/// never seen with a hypothesis:
+///
+/// ```text
+///     p(d|h) = (|(d,h)| + alpha) / (|h| + alpha * |V|)
+/// ```
+///
+/// where |V| is the size of the vocabulary (the number of distinct data values).
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Smoothing {
+    /// No smoothing: p(d|h) = |(d,h)| / |h|.
+    ///
+    /// Values that were not seen with a hypothesis are given a fixed placeholder estimate.
+    None,
+
+    /// Add-one smoothing, equivalent to `Lidstone(1.0)`.
+    Laplace,
+
+    /// Add-alpha smoothing, where |V| is the number of distinct values seen during training.
+    ///
+    /// Alpha must be positive and finite.
+    Lidstone(f64),
+
+    /// Add-alpha smoothing with an explicit vocabulary size |V|.
+    ///
+    /// Useful when the full vocabulary is known in advance and is larger than the vocabulary
+    /// seen in the training data, for example all 256 values of a byte.
+    Vocabulary {
+        /// The pseudo-count added to each value, which must be positive and finite.
+        alpha: f64,
+        /// The number of distinct values that can occur.
+        size: usize,
+    },
+}
+
+impl Default for Smoothing {
+    fn default() -> Self {
+        Smoothing::None
+    }
+}
+
+impl Smoothing {
+    /// Estimate log2(p(d|h)) for a value seen during training.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - count of |(d,h)|
+    /// * `total` - count of |h|
+    /// * `vocabulary` - number of distinct values seen during training
+    ///
+    pub(crate) fn log2_likelihood(&self, count: f64, total: f64, vocabulary: usize) -> f64 {
+        match self.alpha_and_vocabulary(vocabulary) {
+            Some((alpha, v)) => ((count + alpha) / (total + alpha * v)).log2(),
+            None => (count / total).log2(),
+        }
+    }
+
+    /// Estimate log2(p(d|h)) for a value that was not seen with the hypothesis during training.
+    ///
+    /// # Arguments
+    ///
+    /// * `total` - count of |h|
+    /// * `vocabulary` - number of distinct values seen during training
+    ///
+    pub(crate) fn log2_unseen(&self, total: f64, vocabulary: usize) -> f64 {
+        match self.alpha_and_vocabulary(vocabulary) {
+            Some((alpha, v)) => (alpha / (total + alpha * v)).log2(),
+            None => LOG2_PLACEHOLDER_PROBABILITY,
+        }
+    }
+
+    /// Estimate log2(p(d|h)) and log2(1 - p(d|h)) for the presence of a value in a document.
+    ///
+    /// Presence is a binary outcome, so the vocabulary used in the denominator is 2 regardless of
+    /// the number of distinct values seen during training.
+    /// Without smoothing, both estimates are limited to the placeholder estimate, as a presence
+    /// probability of exactly 0 or 1 would rule out a hypothesis entirely.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - count of documents with hypothesis h that contain d
+    /// * `total` - count of documents with hypothesis h
+    ///
+    pub(crate) fn log2_presence(&self, count: f64, total: f64) -> (f64, f64) {
+        match self.alpha_and_vocabulary(2) {
+            Some((alpha, _)) => {
+                let p = (count + alpha) / (total + 2.0 * alpha);
+                (p.log2(), (1.0 - p).log2())
+            }
+            None => {
+                let p = count / total;
+                (
+                    p.log2().max(LOG2_PLACEHOLDER_PROBABILITY),
+                    (1.0 - p).log2().max(LOG2_PLACEHOLDER_PROBABILITY),
+                )
+            }
+        }
+    }
+
+    /// Check that the pseudo-count is positive and finite, as any other pseudo-count makes the
+    /// estimates negative or NaN.
+    pub(crate) fn check(&self) -> Result<(), Error> {
+        match *self {
+            Smoothing::Lidstone(alpha) | Smoothing::Vocabulary { alpha, .. }
+                if !(alpha.is_finite() && alpha > 0.0) =>
+            {
+                Err(Error::InvalidSmoothing)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn alpha_and_vocabulary(&self, vocabulary: usize) -> Option<(f64, f64)> {
+        match *self {
+            Smoothing::None => None,
+            Smoothing::Laplace => Some((1.0, vocabulary as f64)),
+            Smoothing::Lidstone(alpha) => Some((alpha, vocabulary as f64)),
+            Smoothing::Vocabulary { alpha, size } => Some((alpha, size.max(vocabulary) as f64)),
+        }
+    }
+}
@@ -1,37 +1,146 @@
-use crate::posteriors::posteriors;
+use crate::error::Error;
+use crate::frozenclassifier::encode_frozen;
+#[cfg(feature = "serde")]
+use crate::interning::{extern_rows, extern_values};
+use crate::interning::{hypothesis_table, intern_rows, intern_values};
+use crate::modelfile::{decode, encode, ModelKeyOwned, KIND_STREAM};
+use crate::precision::{cast_rows, cast_values};
+use crate::quantization::Bucket;
+use crate::quantizedclassifier::QuantizedClassifier;
 use crate::results::Results;
-use std::collections::{HashMap, HashSet};
-use std::hash::Hash;
+use crate::scratch::Scratch;
+use crate::unseen::UnseenPolicy;
+use num_traits::Float;
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct StreamClassifier<D, H>
+#[derive(Clone, Debug)]
+pub struct StreamClassifier<D, H, F = f64, S = RandomState>
 where
-    D: Copy + Eq + Hash,
-    H: Copy + Eq + Hash,
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    F: Float,
+    S: BuildHasher,
 {
-    log_priors: HashMap<H, f64>,
-    // probability P is: P(D|H) * P(H)
-    log_likelihoods: HashMap<D, Vec<(H, f64)>>,
+    // each hypothesis, indexed by its id (see interning.rs)
+    hypotheses: Vec<H>,
+    // probability of each hypothesis, by id
+    log_priors: Vec<F>,
+    // probability P is: P(D|H) * P(H), with each H as an id
+    log_likelihoods: HashMap<D, Vec<(u32, F)>, S>,
+    // probability P(D|H) assumed for values not seen with H during training, by id
+    log_unseen: Vec<F>,
+    unseen_policy: UnseenPolicy,
 }
 
-impl<D: Copy + Eq + Hash, H: Copy + Eq + Hash> StreamClassifier<D, H> {
+impl<D, H, F, S> PartialEq for StreamClassifier<D, H, F, S>
+where
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    F: Float,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.hypotheses == other.hypotheses
+            && self.log_priors == other.log_priors
+            && self.log_likelihoods == other.log_likelihoods
+            && self.log_unseen == other.log_unseen
+            && self.unseen_policy == other.unseen_policy
+    }
+}
+
+impl<D, H, F, S> StreamClassifier<D, H, F, S>
+where
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    F: Float,
+    S: BuildHasher + Default,
+{
     /// Create a new Classifier.
     ///
     /// # Arguments
     ///
     /// * `log_priors` - probability of hypothesis
-    /// * `log_likelihoods` - probability of data given hypothesis, for each input array position
+    /// * `log_likelihoods` - probability of data given hypothesis
+    /// * `log_unseen` - probability of unseen data given hypothesis
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(StreamClassifier)` - the classifier.
+    /// * `Err(Error::InconsistentModel)` - a likelihood refers to a hypothesis with no
+    ///   probability, or a hypothesis has no probability for unseen data.
     ///
     pub(crate) fn new(
-        log_priors: HashMap<H, f64>,
-        log_likelihoods: HashMap<D, Vec<(H, f64)>>,
-    ) -> Self {
-        StreamClassifier {
-            log_priors,
+        log_priors: HashMap<H, F, S>,
+        log_likelihoods: HashMap<D, Vec<(H, F)>, S>,
+        log_unseen: HashMap<H, F, S>,
+    ) -> Result<Self, Error> {
+        let (hypotheses, ids) = hypothesis_table(&log_priors);
+        let log_likelihoods = intern_rows(log_likelihoods, &ids).ok_or(Error::InconsistentModel)?;
+        let log_unseen = intern_values(&log_unseen, &hypotheses).ok_or(Error::InconsistentModel)?;
+        Ok(StreamClassifier {
+            log_priors: intern_values(&log_priors, &hypotheses).unwrap(),
+            hypotheses,
             log_likelihoods,
+            log_unseen,
+            unseen_policy: UnseenPolicy::default(),
+        })
+    }
+
+    /// Set how data values that a hypothesis did not see during training are scored.
+    ///
+    /// # Arguments
+    ///
+    /// * `unseen_policy` - the policy applied to unseen values
+    ///
+    pub fn with_unseen_policy(mut self, unseen_policy: UnseenPolicy) -> Self {
+        self.unseen_policy = unseen_policy;
+        self
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn unseen_policy(&self) -> UnseenPolicy {
+        self.unseen_policy
+    }
+
+    /// Convert the probabilities of the Classifier to another floating point type, for example
+    /// `f32` to halve the memory used by the model.
+    ///
+    /// # Return Value
+    ///
+    /// * `StreamClassifier` type
+    ///
+    pub fn cast<G: Float>(&self) -> StreamClassifier<D, H, G, S> {
+        StreamClassifier {
+            hypotheses: self.hypotheses.clone(),
+            log_priors: cast_values(&self.log_priors),
+            log_likelihoods: cast_rows(&self.log_likelihoods),
+            log_unseen: cast_values(&self.log_unseen),
+            unseen_policy: self.unseen_policy,
         }
     }
 
+    /// Make a copy of the Classifier that stores each log likelihood as a bucket of type `Q`,
+    /// `u8` or `u16`, rather than as a floating point value.
+    ///
+    /// Use `QuantizedClassifier::report` to measure the effect on accuracy.
+    ///
+    /// # Return Value
+    ///
+    /// * `QuantizedClassifier` type
+    ///
+    pub fn quantize<Q: Bucket>(&self) -> QuantizedClassifier<D, H, Q, S> {
+        QuantizedClassifier::new(
+            self.hypotheses.clone(),
+            cast_values(&self.log_priors),
+            &cast_rows(&self.log_likelihoods),
+            cast_values(&self.log_unseen),
+            self.unseen_policy,
+        )
+    }
+
     /// Classify an unknown input.
     ///
     /// # Arguments
@@ -42,25 +151,302 @@ impl<D: Copy + Eq + Hash, H: Copy + Eq + Hash> StreamClassifier<D, H> {
     ///
     /// * `Results` type
     ///
-    pub fn classify(&self, stream: &mut dyn Iterator<Item = D>) -> Results<H> {
-        const LOG2_PLACEHOLDER_PROBABILITY: f64 = -(f64::MANTISSA_DIGITS as f64);
-
-        // Accumulate product of likelihoods, grouped by hypothesis.
-        let mut log_likelihoods: HashMap<H, f64> = HashMap::default();
-        let placeholder = Vec::new();
-        let all: HashSet<&H> = self.log_priors.keys().collect();
-        for d in stream {
-            let mut missing = all.clone();
-            let found = self.log_likelihoods.get(&d).unwrap_or(&placeholder);
-            for (h, p) in found {
-                *log_likelihoods.entry(*h).or_insert(0.0) += p;
-                missing.remove(h);
-            }
-            for h in missing {
-                *log_likelihoods.entry(*h).or_insert(0.0) += LOG2_PLACEHOLDER_PROBABILITY;
-            }
+    /// # Panics
+    ///
+    /// * if the policy is `UnseenPolicy::Error` and a value was not seen by any hypothesis, see
+    ///   `try_classify`.
+    ///
+    pub fn classify(&self, stream: &mut dyn Iterator<Item = D>) -> Results<H, F> {
+        self.try_classify(stream).unwrap()
+    }
+
+    /// Classify an unknown input, failing if the `UnseenPolicy` rejects a value.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - a stream of data to classify for a single hypothesis.
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(Results)` - the classification results.
+    /// * `Err(Error::UnseenValue)` - a value was not seen by any hypothesis during training.
+    ///
+    pub fn try_classify(
+        &self,
+        stream: &mut dyn Iterator<Item = D>,
+    ) -> Result<Results<H, F>, Error> {
+        let mut results = Results::default();
+        self.score::<D, D>(&mut Scratch::new(), stream, &mut results)?;
+        Ok(results)
+    }
+
+    /// Classify an unknown input of borrowed values, for example `&str` values when the data
+    /// type is `String`, without converting each value to the data type.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - a stream of borrowed data to classify for a single hypothesis.
+    ///
+    /// # Return Value
+    ///
+    /// * `Results` type
+    ///
+    /// # Panics
+    ///
+    /// * if the policy is `UnseenPolicy::Error` and a value was not seen by any hypothesis, see
+    ///   `try_classify_borrowed`.
+    ///
+    pub fn classify_borrowed<'q, Q>(&self, stream: &mut dyn Iterator<Item = &'q Q>) -> Results<H, F>
+    where
+        D: Borrow<Q>,
+        Q: Eq + Hash + ?Sized + 'q,
+    {
+        self.try_classify_borrowed(stream).unwrap()
+    }
+
+    /// Classify an unknown input of borrowed values, failing if the `UnseenPolicy` rejects a
+    /// value.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - a stream of borrowed data to classify for a single hypothesis.
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(Results)` - the classification results.
+    /// * `Err(Error::UnseenValue)` - a value was not seen by any hypothesis during training.
+    ///
+    pub fn try_classify_borrowed<'q, Q>(
+        &self,
+        stream: &mut dyn Iterator<Item = &'q Q>,
+    ) -> Result<Results<H, F>, Error>
+    where
+        D: Borrow<Q>,
+        Q: Eq + Hash + ?Sized + 'q,
+    {
+        let mut results = Results::default();
+        self.score::<Q, &Q>(&mut Scratch::new(), stream, &mut results)?;
+        Ok(results)
+    }
+
+    /// Classify an unknown input into existing results, reusing working space, so that
+    /// classifying many inputs performs no heap allocation once the working space has grown.
+    ///
+    /// # Arguments
+    ///
+    /// * `scratch` - working space, which can be reused for any number of calls
+    /// * `stream` - a stream of data to classify for a single hypothesis.
+    /// * `results` - the results to overwrite with the classification results
+    ///
+    /// # Panics
+    ///
+    /// * if the policy is `UnseenPolicy::Error` and a value was not seen by any hypothesis, see
+    ///   `try_classify_into`.
+    ///
+    pub fn classify_into(
+        &self,
+        scratch: &mut Scratch<F>,
+        stream: &mut dyn Iterator<Item = D>,
+        results: &mut Results<H, F>,
+    ) {
+        self.try_classify_into(scratch, stream, results).unwrap()
+    }
+
+    /// Classify an unknown input into existing results, reusing working space, failing if the
+    /// `UnseenPolicy` rejects a value.
+    ///
+    /// # Arguments
+    ///
+    /// * `scratch` - working space, which can be reused for any number of calls
+    /// * `stream` - a stream of data to classify for a single hypothesis.
+    /// * `results` - the results to overwrite with the classification results
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(())` - `results` holds the classification results.
+    /// * `Err(Error::UnseenValue)` - a value was not seen by any hypothesis during training.
+    ///   `results` is unchanged.
+    ///
+    pub fn try_classify_into(
+        &self,
+        scratch: &mut Scratch<F>,
+        stream: &mut dyn Iterator<Item = D>,
+        results: &mut Results<H, F>,
+    ) -> Result<(), Error> {
+        self.score::<D, D>(scratch, stream, results)
+    }
+
+    // Classify a stream of values that can be borrowed in the same form as the data type.
+    fn score<Q, T>(
+        &self,
+        scratch: &mut Scratch<F>,
+        stream: &mut dyn Iterator<Item = T>,
+        results: &mut Results<H, F>,
+    ) -> Result<(), Error>
+    where
+        D: Borrow<Q>,
+        T: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        // Accumulate product of likelihoods, by hypothesis id.
+        scratch.reset(self.hypotheses.len());
+        for (i, d) in stream.enumerate() {
+            let found: &[(u32, F)] = match self.log_likelihoods.get(d.borrow()) {
+                Some(found) => found,
+                None => match self.unseen_policy {
+                    UnseenPolicy::Skip => continue,
+                    UnseenPolicy::Error => return Err(Error::UnseenValue { position: i }),
+                    _ => &[],
+                },
+            };
+            scratch.add(
+                i,
+                found.iter().copied(),
+                &self.log_unseen,
+                self.unseen_policy,
+                |p| p,
+            );
         }
 
-        posteriors::<D, H>(&self.log_priors, &log_likelihoods)
+        scratch.posteriors_into(&self.hypotheses, &self.log_priors, results);
+        Ok(())
+    }
+}
+
+impl<D, H, F, S> StreamClassifier<D, H, F, S>
+where
+    D: Clone + Eq + Hash + ModelKeyOwned,
+    H: Clone + Eq + Hash + ModelKeyOwned,
+    F: Float,
+    S: BuildHasher + Default,
+{
+    /// Encode the Classifier in the binary model file format.
+    ///
+    /// # Return Value
+    ///
+    /// * `Vec<u8>` - the encoded model, which can be written to a file.
+    ///
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode(
+            KIND_STREAM,
+            &self.hypotheses,
+            &self.log_priors,
+            std::slice::from_ref(&self.log_likelihoods),
+            std::slice::from_ref(&self.log_unseen),
+            self.unseen_policy,
+        )
+    }
+
+    /// Decode a Classifier from the binary model file format.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - the encoded model, as returned by `to_bytes`
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(StreamClassifier)` - the decoded model.
+    /// * `Err(Error::Truncated)` - `bytes` ends before the model is complete.
+    /// * `Err(Error::InvalidFormat)` - `bytes` is not a valid `StreamClassifier` model.
+    /// * `Err(Error::UnsupportedVersion)` - `bytes` was written by a newer format version.
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let mut model = decode(bytes, KIND_STREAM)?;
+        if model.log_likelihoods.len() != 1 {
+            return Err(Error::InvalidFormat);
+        }
+        Ok(StreamClassifier {
+            hypotheses: model.hypotheses,
+            log_priors: model.log_priors,
+            log_likelihoods: model.log_likelihoods.pop().unwrap(),
+            log_unseen: model.log_unseen.pop().unwrap(),
+            unseen_policy: model.unseen_policy,
+        })
+    }
+}
+
+impl<D, H, F, S> StreamClassifier<D, H, F, S>
+where
+    D: Clone + Eq + Hash + Ord + ModelKeyOwned,
+    H: Clone + Eq + Hash + ModelKeyOwned,
+    F: Float,
+    S: BuildHasher + Default,
+{
+    /// Encode the Classifier as a frozen model, for use with `FrozenClassifier`.
+    ///
+    /// # Return Value
+    ///
+    /// * `Vec<u8>` - the encoded model, which can be written to a file and memory-mapped.
+    ///
+    pub fn to_frozen_bytes(&self) -> Vec<u8> {
+        encode_frozen(
+            &self.hypotheses,
+            &self.log_priors,
+            &self.log_likelihoods,
+            &self.log_unseen,
+            self.unseen_policy,
+        )
+    }
+}
+
+// The serialized form of a StreamClassifier, which refers to hypotheses by value.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(bound(
+    serialize = "D: serde::Serialize, H: serde::Serialize, F: serde::Serialize",
+    deserialize = "D: serde::Deserialize<'de>, H: serde::Deserialize<'de>, \
+                   F: serde::Deserialize<'de>, S: BuildHasher + Default"
+))]
+struct StreamClassifierEntries<D, H, F, S>
+where
+    D: Clone + Eq + Hash,
+    H: Clone + Eq + Hash,
+    S: BuildHasher,
+{
+    #[serde(with = "crate::serialization::entries")]
+    log_priors: HashMap<H, F, S>,
+    #[serde(with = "crate::serialization::entries")]
+    log_likelihoods: HashMap<D, Vec<(H, F)>, S>,
+    #[serde(with = "crate::serialization::entries")]
+    log_unseen: HashMap<H, F, S>,
+    unseen_policy: UnseenPolicy,
+}
+
+#[cfg(feature = "serde")]
+impl<D, H, F, S> serde::Serialize for StreamClassifier<D, H, F, S>
+where
+    D: Clone + Eq + Hash + serde::Serialize,
+    H: Clone + Eq + Hash + serde::Serialize,
+    F: Float + serde::Serialize,
+    S: BuildHasher + Default,
+{
+    fn serialize<T: serde::Serializer>(&self, serializer: T) -> Result<T::Ok, T::Error> {
+        StreamClassifierEntries {
+            log_priors: extern_values::<_, _, S>(&self.log_priors, &self.hypotheses),
+            log_likelihoods: extern_rows(&self.log_likelihoods, &self.hypotheses),
+            log_unseen: extern_values(&self.log_unseen, &self.hypotheses),
+            unseen_policy: self.unseen_policy,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, D, H, F, S> serde::Deserialize<'de> for StreamClassifier<D, H, F, S>
+where
+    D: Clone + Eq + Hash + serde::Deserialize<'de>,
+    H: Clone + Eq + Hash + serde::Deserialize<'de>,
+    F: Float + serde::Deserialize<'de>,
+    S: BuildHasher + Default,
+{
+    fn deserialize<T: serde::Deserializer<'de>>(deserializer: T) -> Result<Self, T::Error> {
+        let entries = StreamClassifierEntries::<D, H, F, S>::deserialize(deserializer)?;
+        let classifier = StreamClassifier::new(
+            entries.log_priors,
+            entries.log_likelihoods,
+            entries.log_unseen,
+        )
+        .map_err(serde::de::Error::custom)?;
+        Ok(classifier.with_unseen_policy(entries.unseen_policy))
     }
 }
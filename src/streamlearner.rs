@@ -1,32 +1,86 @@
-use crate::likelihoods::likelihoods;
+use crate::complementclassifier::ComplementClassifier;
+use crate::counts::{
+    add, check_subtract, check_subtract_one, check_weight, subtract, subtract_one,
+};
+use crate::error::Error;
+use crate::likelihoods::{complement_likelihoods, likelihoods, priors, unseen_likelihoods};
+use crate::quantization::Bucket;
+use crate::quantizedclassifier::QuantizedClassifier;
+use crate::smoothing::Smoothing;
 use crate::streamclassifier::StreamClassifier;
+use std::collections::hash_map::RandomState;
 use std::collections::HashMap;
 use std::fmt::Debug;
-use std::hash::Hash;
+use std::hash::{BuildHasher, Hash};
 use std::iter::Iterator;
+use std::iter::Sum;
+use std::ops::Add;
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct StreamLearner<D, H>
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "D: serde::Serialize, H: serde::Serialize",
+        deserialize = "D: serde::Deserialize<'de>, H: serde::Deserialize<'de>, \
+                       S: BuildHasher + Default"
+    ))
+)]
+pub struct StreamLearner<D, H, S = RandomState>
 where
-    D: Copy + Debug + Eq + Hash,
-    H: Copy + Debug + Eq + Hash,
+    D: Clone + Debug + Eq + Hash,
+    H: Clone + Debug + Eq + Hash,
+    S: BuildHasher,
 {
-    count_hypotheses: HashMap<H, f64>,
-    count_joint: HashMap<(D, H), f64>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::counts"))]
+    count_hypotheses: HashMap<H, f64, S>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::counts"))]
+    count_joint: HashMap<(D, H), f64, S>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::count"))]
     count_total: f64,
 }
 
-impl<D: Copy + Debug + Eq + Hash, H: Copy + Debug + Eq + Hash> Default for StreamLearner<D, H> {
+impl<D: Clone + Debug + Eq + Hash, H: Clone + Debug + Eq + Hash> Default for StreamLearner<D, H> {
     fn default() -> Self {
+        Self::with_default_hasher()
+    }
+}
+
+impl<D, H, S> PartialEq for StreamLearner<D, H, S>
+where
+    D: Clone + Debug + Eq + Hash,
+    H: Clone + Debug + Eq + Hash,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.count_hypotheses == other.count_hypotheses
+            && self.count_joint == other.count_joint
+            && self.count_total == other.count_total
+    }
+}
+
+impl<D, H, S> StreamLearner<D, H, S>
+where
+    D: Clone + Debug + Eq + Hash,
+    H: Clone + Debug + Eq + Hash,
+    S: BuildHasher + Default,
+{
+    /// Create a Learner whose maps use the hasher type `S`, for example `FxBuildHasher`.
+    ///
+    /// Unlike `HashMap::with_hasher`, this takes no hasher instance: every map, including the
+    /// maps of the Classifiers made by the Learner, is created with `S::default()`, so the
+    /// hasher is chosen by its type and cannot be seeded per instance.
+    ///
+    /// `default()` creates a Learner that uses the standard library's hasher.
+    ///
+    pub fn with_default_hasher() -> Self {
         StreamLearner {
             count_hypotheses: HashMap::default(),
             count_joint: HashMap::default(),
             count_total: 0.0,
         }
     }
-}
 
-impl<D: Copy + Debug + Eq + Hash, H: Copy + Debug + Eq + Hash> StreamLearner<D, H> {
     /// Update the Learner with a stream of data for a single hypothesis.
     ///
     /// # Arguments
@@ -35,38 +89,366 @@ impl<D: Copy + Debug + Eq + Hash, H: Copy + Debug + Eq + Hash> StreamLearner<D,
     /// * `hypothesis` - the target hypothesis/label/category/classification for the data.
     ///
     pub fn update(&mut self, stream: &mut dyn Iterator<Item = D>, hypothesis: H) -> &mut Self {
+        self.update_weighted(stream, hypothesis, 1.0)
+    }
+
+    /// Update the Learner with a weighted stream of data for a single hypothesis.
+    ///
+    /// Each item in the stream is counted as if it had occurred `weight` times.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - a stream of training data for a single hypothesis.
+    /// * `hypothesis` - the target hypothesis/label/category/classification for the data.
+    /// * `weight` - the importance of the stream, which must be positive and finite
+    ///
+    /// # Panics
+    ///
+    /// * if the weight is zero, negative, infinite or NaN, see `try_update_weighted`.
+    ///
+    pub fn update_weighted(
+        &mut self,
+        stream: &mut dyn Iterator<Item = D>,
+        hypothesis: H,
+        weight: f64,
+    ) -> &mut Self {
+        self.try_update_weighted(stream, hypothesis, weight)
+            .unwrap()
+    }
+
+    /// Update the Learner with a weighted stream of data for a single hypothesis, failing if the
+    /// weight is invalid.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - a stream of training data for a single hypothesis.
+    /// * `hypothesis` - the target hypothesis/label/category/classification for the data.
+    /// * `weight` - the importance of the stream, which must be positive and finite
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(&mut Self)` - the stream was learned.
+    /// * `Err(Error::InvalidWeight)` - the weight is zero, negative, infinite or NaN, and the
+    ///   Learner is unchanged.
+    ///
+    pub fn try_update_weighted(
+        &mut self,
+        stream: &mut dyn Iterator<Item = D>,
+        hypothesis: H,
+        weight: f64,
+    ) -> Result<&mut Self, Error> {
+        check_weight(weight)?;
         let mut count = 0.0;
         for d in stream {
-            *self.count_joint.entry((d, hypothesis)).or_insert(0.0) += 1.0;
+            *self
+                .count_joint
+                .entry((d, hypothesis.clone()))
+                .or_insert(0.0) += weight;
             // Retrieving the length can be expensive, for example when the data is being streamed.
             // This counter is cheap to maintain and can be processed without additional latency.
-            count += 1.0;
+            count += weight;
         }
         // Do this lookup once instead of for each item in data.
         *self.count_hypotheses.entry(hypothesis).or_insert(0.0) += count;
         self.count_total += count;
+        Ok(self)
+    }
+
+    /// Remove a previously learned stream of data for a single hypothesis.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - a stream of training data for a single hypothesis.
+    /// * `hypothesis` - the target hypothesis/label/category/classification for the data.
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(&mut Self)` - the stream was removed.
+    /// * `Err(Error::NegativeCount)` - the stream was not learned, and the Learner is unchanged.
+    ///
+    pub fn forget(
+        &mut self,
+        stream: &mut dyn Iterator<Item = D>,
+        hypothesis: H,
+    ) -> Result<&mut Self, Error> {
+        self.forget_weighted(stream, hypothesis, 1.0)
+    }
+
+    /// Remove a previously learned weighted stream of data for a single hypothesis.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - a stream of training data for a single hypothesis.
+    /// * `hypothesis` - the target hypothesis/label/category/classification for the data.
+    /// * `weight` - the weight that the stream was learned with
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(&mut Self)` - the stream was removed.
+    /// * `Err(Error::NegativeCount)` - the stream was not learned with at least this weight, and
+    ///   the Learner is unchanged.
+    /// * `Err(Error::InvalidWeight)` - the weight is zero, negative, infinite or NaN, and the
+    ///   Learner is unchanged.
+    ///
+    pub fn forget_weighted(
+        &mut self,
+        stream: &mut dyn Iterator<Item = D>,
+        hypothesis: H,
+        weight: f64,
+    ) -> Result<&mut Self, Error> {
+        check_weight(weight)?;
+        // The stream can only be read once, so collect the counts before checking them.
+        let mut deltas: HashMap<(D, H), f64, S> = HashMap::default();
+        let mut count = 0.0;
+        for d in stream {
+            *deltas.entry((d, hypothesis.clone())).or_insert(0.0) += weight;
+            count += weight;
+        }
+        let delta_hypotheses: HashMap<H, f64, S> = [(hypothesis, count)].into_iter().collect();
+        check_subtract(&self.count_joint, &deltas)?;
+        check_subtract(&self.count_hypotheses, &delta_hypotheses)?;
+        check_subtract_one(self.count_total, count)?;
+
+        subtract(&mut self.count_joint, &deltas);
+        subtract(&mut self.count_hypotheses, &delta_hypotheses);
+        subtract_one(&mut self.count_total, count);
+        Ok(self)
+    }
+
+    /// Add the training of another Learner to this Learner.
+    ///
+    /// The result is the same as if this Learner had also been updated with all of the other
+    /// Learner's training data, so Learners can be trained separately on disjoint shards of data
+    /// and then combined.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - the Learner to merge into this Learner
+    ///
+    pub fn merge(&mut self, other: &Self) -> &mut Self {
+        add(&mut self.count_joint, &other.count_joint);
+        add(&mut self.count_hypotheses, &other.count_hypotheses);
+        self.count_total += other.count_total;
         self
     }
 
     /// Make a classifier based on a snapshot of the current Learner's training.
     ///
+    /// No smoothing is applied, see `make_classifier_with_smoothing`.
+    ///
+    /// # Return Value
+    ///
+    /// * `StreamClassifier` type
+    ///
+    pub fn make_classifier(&mut self) -> StreamClassifier<D, H, f64, S> {
+        self.make_classifier_with_smoothing(Smoothing::None)
+    }
+
+    /// Make a classifier based on a snapshot of the current Learner's training, estimating
+    /// likelihoods with the given smoothing.
+    ///
     /// # Arguments
     ///
-    /// * `data` - a stream of data representing a single hypothesis.
-    /// * `hypothesis` - the single target hypothesis/label/category/classification for the data
+    /// * `smoothing` - the smoothing applied to the likelihood of each data value
     ///
     /// # Return Value
     ///
     /// * `StreamClassifier` type
     ///
-    pub fn make_classifier(&mut self) -> StreamClassifier<D, H> {
-        let log_priors: HashMap<H, f64> = self
+    /// # Panics
+    ///
+    /// * if the smoothing alpha is not positive and finite, if the Learner has only learned
+    ///   empty streams, or if the Learner was deserialised from corrupt data, see
+    ///   `try_make_classifier_with_smoothing`.
+    ///
+    pub fn make_classifier_with_smoothing(
+        &mut self,
+        smoothing: Smoothing,
+    ) -> StreamClassifier<D, H, f64, S> {
+        self.classifier(smoothing).unwrap()
+    }
+
+    /// Make a classifier based on a snapshot of the current Learner's training, failing if the
+    /// Learner has no training data or is inconsistent.
+    ///
+    /// No smoothing is applied, see `try_make_classifier_with_smoothing`.
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(StreamClassifier)` - the classifier.
+    /// * `Err(Error::EmptyModel)` - the Learner has no training data.
+    /// * `Err(Error::EmptyData)` - the Learner has only learned empty streams.
+    /// * `Err(Error::InconsistentModel)` - the Learner was deserialised from corrupt data.
+    ///
+    pub fn try_make_classifier(&mut self) -> Result<StreamClassifier<D, H, f64, S>, Error> {
+        self.try_make_classifier_with_smoothing(Smoothing::None)
+    }
+
+    /// Make a classifier based on a snapshot of the current Learner's training, estimating
+    /// likelihoods with the given smoothing, failing if the Learner has no training data or is
+    /// inconsistent.
+    ///
+    /// # Arguments
+    ///
+    /// * `smoothing` - the smoothing applied to the likelihood of each data value
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(StreamClassifier)` - the classifier.
+    /// * `Err(Error::EmptyModel)` - the Learner has no training data.
+    /// * `Err(Error::EmptyData)` - the Learner has only learned empty streams.
+    /// * `Err(Error::InvalidSmoothing)` - the smoothing alpha is not positive and finite.
+    /// * `Err(Error::InconsistentModel)` - the Learner was deserialised from corrupt data.
+    ///
+    pub fn try_make_classifier_with_smoothing(
+        &mut self,
+        smoothing: Smoothing,
+    ) -> Result<StreamClassifier<D, H, f64, S>, Error> {
+        if self.count_hypotheses.is_empty() {
+            return Err(Error::EmptyModel);
+        }
+        self.classifier(smoothing)
+    }
+
+    /// Make a classifier based on a snapshot of the current Learner's training, estimating
+    /// likelihoods with the given smoothing and storing each log likelihood as a bucket of type
+    /// `Q`, `u8` or `u16`.
+    ///
+    /// See `StreamClassifier::quantize`.
+    ///
+    /// # Arguments
+    ///
+    /// * `smoothing` - the smoothing applied to the likelihood of each data value
+    ///
+    /// # Return Value
+    ///
+    /// * `QuantizedClassifier` type
+    ///
+    /// # Panics
+    ///
+    /// * if the smoothing alpha is not positive and finite, if the Learner has only learned
+    ///   empty streams, or if the Learner was deserialised from corrupt data, see
+    ///   `try_make_classifier_with_smoothing`.
+    ///
+    pub fn make_quantized_classifier<Q: Bucket>(
+        &mut self,
+        smoothing: Smoothing,
+    ) -> QuantizedClassifier<D, H, Q, S> {
+        self.make_classifier_with_smoothing(smoothing).quantize()
+    }
+
+    // Make a classifier, failing if the smoothing is invalid or the counts are inconsistent.
+    fn classifier(&self, smoothing: Smoothing) -> Result<StreamClassifier<D, H, f64, S>, Error> {
+        smoothing.check()?;
+        let log_priors: HashMap<H, f64, S> = priors(&self.count_hypotheses, self.count_total)?;
+        let log_likelihoods: HashMap<D, Vec<(H, f64)>, S> =
+            likelihoods(&self.count_hypotheses, &self.count_joint, smoothing)?;
+        let log_unseen: HashMap<H, f64, S> =
+            unseen_likelihoods(&self.count_hypotheses, &self.count_joint, smoothing);
+        StreamClassifier::new(log_priors, log_likelihoods, log_unseen)
+    }
+
+    /// Make a Complement Naive Bayes classifier based on a snapshot of the current Learner's
+    /// training.
+    ///
+    /// The likelihood of each value given h is estimated from the training data of all
+    /// hypotheses except h, which reduces the bias towards hypotheses with more training data.
+    /// The priors are uniform, so the results rank hypotheses but are not calibrated
+    /// probabilities.
+    ///
+    /// # Arguments
+    ///
+    /// * `smoothing` - the smoothing applied to the complement likelihood of each data value
+    /// * `normalise` - whether to normalise the weights of each hypothesis to sum to 1
+    ///
+    /// # Return Value
+    ///
+    /// * `ComplementClassifier` type
+    ///
+    /// # Panics
+    ///
+    /// * if the smoothing alpha is not positive and finite, see
+    ///   `try_make_complement_classifier`.
+    ///
+    pub fn make_complement_classifier(
+        &mut self,
+        smoothing: Smoothing,
+        normalise: bool,
+    ) -> ComplementClassifier<D, H, f64, S> {
+        self.complement_classifier(smoothing, normalise).unwrap()
+    }
+
+    /// Make a Complement Naive Bayes classifier based on a snapshot of the current Learner's
+    /// training, failing if the Learner has no training data or the smoothing is invalid.
+    ///
+    /// See `make_complement_classifier`.
+    ///
+    /// # Arguments
+    ///
+    /// * `smoothing` - the smoothing applied to the complement likelihood of each data value
+    /// * `normalise` - whether to normalise the weights of each hypothesis to sum to 1
+    ///
+    /// # Return Value
+    ///
+    /// * `Ok(ComplementClassifier)` - the classifier.
+    /// * `Err(Error::EmptyModel)` - the Learner has no training data.
+    /// * `Err(Error::InvalidSmoothing)` - the smoothing alpha is not positive and finite.
+    ///
+    pub fn try_make_complement_classifier(
+        &mut self,
+        smoothing: Smoothing,
+        normalise: bool,
+    ) -> Result<ComplementClassifier<D, H, f64, S>, Error> {
+        if self.count_hypotheses.is_empty() {
+            return Err(Error::EmptyModel);
+        }
+        self.complement_classifier(smoothing, normalise)
+    }
+
+    // Make a complement classifier, failing if the smoothing is invalid.
+    fn complement_classifier(
+        &self,
+        smoothing: Smoothing,
+        normalise: bool,
+    ) -> Result<ComplementClassifier<D, H, f64, S>, Error> {
+        smoothing.check()?;
+        let log_uniform = -(self.count_hypotheses.len() as f64).log2();
+        let log_priors: HashMap<H, f64, S> = self
             .count_hypotheses
-            .iter()
-            .map(|(h, c)| (*h, (*c / self.count_total).log2()))
+            .keys()
+            .map(|h| (h.clone(), log_uniform))
             .collect();
-        let log_likelihoods: HashMap<D, Vec<(H, f64)>> =
-            likelihoods(&self.count_hypotheses, &self.count_joint);
-        StreamClassifier::new(log_priors, log_likelihoods)
+        let (weights, unseen_weights) = complement_likelihoods(
+            &self.count_hypotheses,
+            &self.count_joint,
+            smoothing,
+            normalise,
+        );
+        ComplementClassifier::new(log_priors, weights, unseen_weights)
+    }
+}
+
+impl<D, H, S> Add for StreamLearner<D, H, S>
+where
+    D: Clone + Debug + Eq + Hash,
+    H: Clone + Debug + Eq + Hash,
+    S: BuildHasher + Default,
+{
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self::Output {
+        self.merge(&rhs);
+        self
+    }
+}
+
+impl<D, H, S> Sum for StreamLearner<D, H, S>
+where
+    D: Clone + Debug + Eq + Hash,
+    H: Clone + Debug + Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::with_default_hasher(), Add::add)
     }
 }
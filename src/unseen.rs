@@ -0,0 +1,55 @@
+use crate::precision::cast;
+use crate::smoothing::LOG2_PLACEHOLDER_PROBABILITY;
+use num_traits::Float;
+
+/// How a Classifier scores data values that a hypothesis did not see during training.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UnseenPolicy {
+    /// Add a constant log2 probability for each hypothesis that did not see a value.
+    ///
+    /// This applies even when no hypothesis saw the value.
+    Penalty(f64),
+
+    /// Ignore values that were not seen by any hypothesis.
+    ///
+    /// Values seen by some hypotheses are scored for the remaining hypotheses using the
+    /// per-class estimate from the Learner's smoothing.
+    Skip,
+
+    /// Use the per-class estimate from the Learner's smoothing for every value that a hypothesis
+    /// did not see, including values not seen by any hypothesis.
+    ///
+    /// Without smoothing, this is equivalent to `Penalty(-53.0)`.
+    Smoothed,
+
+    /// Fail classification on a value that was not seen by any hypothesis.
+    ///
+    /// Values seen by some hypotheses are scored for the remaining hypotheses using the
+    /// per-class estimate from the Learner's smoothing.
+    Error,
+}
+
+impl Default for UnseenPolicy {
+    fn default() -> Self {
+        UnseenPolicy::Smoothed
+    }
+}
+
+impl UnseenPolicy {
+    /// A constant penalty equal to the estimate used when the Learner applies no smoothing.
+    pub const PLACEHOLDER: UnseenPolicy = UnseenPolicy::Penalty(LOG2_PLACEHOLDER_PROBABILITY);
+
+    /// Return the log2 probability of a value that a hypothesis did not see during training.
+    ///
+    /// # Arguments
+    ///
+    /// * `smoothed` - the per-class estimate from the Learner's smoothing
+    ///
+    pub(crate) fn log2_unseen<F: Float>(&self, smoothed: F) -> F {
+        match *self {
+            UnseenPolicy::Penalty(penalty) => cast(penalty),
+            _ => smoothed,
+        }
+    }
+}
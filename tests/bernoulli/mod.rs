@@ -0,0 +1 @@
+mod subject_lines;
@@ -0,0 +1,72 @@
+use assert_approx_eq::assert_approx_eq;
+use naive_bayes::BernoulliClassifier;
+use naive_bayes::BernoulliLearner;
+use naive_bayes::Smoothing;
+
+#[test]
+fn classifies_present_tokens() {
+    let classifier = learn();
+
+    let actual = classifier.classify(&mut "free offer today".split(' '));
+
+    assert_eq!(actual.best().unwrap().0, "spam");
+}
+
+#[test]
+fn classifies_absent_tokens() {
+    let classifier = learn();
+
+    // 'free' occurs in every spam subject line, so its absence is strong evidence.
+    let actual = classifier.classify(&mut "offer today".split(' '));
+
+    assert_eq!(actual.best().unwrap().0, "ham");
+}
+
+#[test]
+fn ignores_repeated_and_unseen_tokens() {
+    let classifier = learn();
+
+    let expected = classifier.classify(&mut "meeting".split(' '));
+    let actual = classifier.classify(&mut "meeting meeting unknown".split(' '));
+
+    for (h, p) in actual {
+        let e = expected.clone().into_iter().find(|x| x.0 == h).unwrap();
+        assert_approx_eq!(p, e.1, 0.000001);
+    }
+}
+
+#[test]
+fn classifies_with_exact_probabilities() {
+    let classifier = BernoulliLearner::default()
+        .update(&mut ['a'].into_iter(), "01")
+        .update(&mut ['b'].into_iter(), "02")
+        .make_classifier_with_smoothing(Smoothing::Laplace);
+
+    let actual = classifier.classify(&mut ['a'].into_iter());
+
+    // p(a|01) = 2/3, p(b|01) = 1/3, p(a|02) = 1/3, p(b|02) = 2/3
+    // p(x|01) = 2/3 * (1 - 1/3) = 4/9, p(x|02) = 1/3 * (1 - 2/3) = 1/9
+    assert_eq!(actual.best().unwrap().0, "01");
+    assert_approx_eq!(actual.best().unwrap().1, 0.8, 0.000001);
+}
+
+fn learn() -> BernoulliClassifier<&'static str, &'static str> {
+    let mut learner = BernoulliLearner::default();
+    for subject in [
+        "free money now",
+        "claim your free prize",
+        "free offer ends today",
+        "free free free",
+    ] {
+        learner.update(&mut subject.split(' '), "spam");
+    }
+    for subject in [
+        "meeting moved to today",
+        "your offer letter",
+        "lunch today",
+        "notes from the meeting",
+    ] {
+        learner.update(&mut subject.split(' '), "ham");
+    }
+    learner.make_classifier()
+}
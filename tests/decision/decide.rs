@@ -0,0 +1,95 @@
+use naive_bayes::{decide, Decision, DecisionPolicy, Results, StreamLearner, UnseenPolicy};
+
+fn results(values: &[(char, f64)]) -> Results<char> {
+    values.iter().copied().collect()
+}
+
+fn learn() -> StreamLearner<char, &'static str> {
+    let mut learner = StreamLearner::default();
+    learner
+        .update(&mut "aaab".chars(), "first")
+        .update(&mut "bbbc".chars(), "second");
+    learner
+}
+
+#[test]
+fn default_policy_accepts_best() {
+    let results = results(&[('a', 0.4), ('b', 0.6)]);
+
+    let actual = decide(&results, &DecisionPolicy::default());
+
+    assert_eq!(actual, Decision::Accept('b'));
+}
+
+#[test]
+fn nothing_to_decide_is_unknown() {
+    let empty: Results<char> = Results::default();
+    let nan = results(&[('a', f64::NAN)]);
+    let unscored = learn().make_classifier().classify(&mut "".chars());
+
+    assert_eq!(decide(&empty, &DecisionPolicy::new()), Decision::Unknown);
+    assert_eq!(decide(&nan, &DecisionPolicy::new()), Decision::Unknown);
+    assert_eq!(decide(&unscored, &DecisionPolicy::new()), Decision::Unknown);
+}
+
+#[test]
+fn abstains_below_min_posterior() {
+    let results = results(&[('a', 0.3), ('b', 0.7)]);
+
+    let accepted = decide(&results, &DecisionPolicy::new().with_min_posterior(0.7));
+    let abstained = decide(&results, &DecisionPolicy::new().with_min_posterior(0.8));
+
+    assert_eq!(accepted, Decision::Accept('b'));
+    assert_eq!(abstained, Decision::Abstain);
+}
+
+#[test]
+fn class_threshold_replaces_min_posterior() {
+    let results = results(&[('a', 0.3), ('b', 0.7)]);
+    let policy = DecisionPolicy::new().with_min_posterior(0.9);
+
+    let stricter = decide(&results, &policy.clone().with_class_threshold('b', 0.95));
+    let looser = decide(&results, &policy.clone().with_class_threshold('b', 0.5));
+    let other = decide(&results, &policy.with_class_threshold('a', 0.5));
+
+    assert_eq!(stricter, Decision::Abstain);
+    assert_eq!(looser, Decision::Accept('b'));
+    assert_eq!(other, Decision::Abstain);
+}
+
+#[test]
+fn abstains_below_min_margin() {
+    let results = results(&[('a', 0.45), ('b', 0.55)]);
+
+    let accepted = decide(&results, &DecisionPolicy::new().with_min_margin(0.05));
+    let abstained = decide(&results, &DecisionPolicy::new().with_min_margin(0.2));
+
+    assert_eq!(accepted, Decision::Accept('b'));
+    assert_eq!(abstained, Decision::Abstain);
+}
+
+#[test]
+fn unknown_input_is_unknown() {
+    let classifier = learn().make_classifier();
+    let policy = DecisionPolicy::new().with_min_known(2);
+
+    let known = classifier.classify(&mut "aaxy".chars());
+    let unknown = classifier.classify(&mut "axyz".chars());
+
+    assert_eq!(decide(&known, &policy), Decision::Accept("first"));
+    assert_eq!(decide(&unknown, &policy), Decision::Unknown);
+}
+
+#[test]
+fn unknown_takes_precedence_over_abstain() {
+    let classifier = learn()
+        .make_classifier()
+        .with_unseen_policy(UnseenPolicy::Skip);
+    let policy = DecisionPolicy::new()
+        .with_min_known(1)
+        .with_min_posterior(1.0);
+
+    let actual = classifier.classify(&mut "xyz".chars());
+
+    assert_eq!(decide(&actual, &policy), Decision::Unknown);
+}
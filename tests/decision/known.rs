@@ -0,0 +1,88 @@
+use naive_bayes::{
+    BernoulliLearner, Field, FixedLearner, GaussianLearner, MixedLearner, Results, Scratch,
+    StreamLearner, UnseenPolicy, Value,
+};
+
+#[test]
+fn stream_counts_known_values() {
+    let mut learner = StreamLearner::default();
+    learner
+        .update(&mut "ab".chars(), 1)
+        .update(&mut "bc".chars(), 2);
+    let classifier = learner.make_classifier();
+
+    assert_eq!(classifier.classify(&mut "abcx".chars()).known(), 3);
+    assert_eq!(classifier.classify(&mut "aax".chars()).known(), 2);
+    assert_eq!(classifier.classify(&mut "xyz".chars()).known(), 0);
+    let skipped = classifier.with_unseen_policy(UnseenPolicy::Skip);
+    assert_eq!(skipped.classify(&mut "axb".chars()).known(), 2);
+}
+
+#[test]
+fn classify_into_counts_known_values() {
+    let mut learner = StreamLearner::default();
+    learner
+        .update(&mut "ab".chars(), 1)
+        .update(&mut "bc".chars(), 2);
+    let classifier = learner.make_classifier();
+    let mut scratch = Scratch::new();
+    let mut results = Results::default();
+
+    classifier.classify_into(&mut scratch, &mut "abc".chars(), &mut results);
+    assert_eq!(results.known(), 3);
+    classifier.classify_into(&mut scratch, &mut "ax".chars(), &mut results);
+    assert_eq!(results.known(), 1);
+}
+
+#[test]
+fn fixed_counts_known_values() {
+    let mut learner = FixedLearner::default();
+    learner.update(&['a', 'b'], 1).update(&['b', 'c'], 2);
+    let classifier = learner.make_classifier();
+
+    assert_eq!(classifier.classify(&['a', 'c']).known(), 2);
+    assert_eq!(classifier.classify(&['c', 'x']).known(), 0);
+}
+
+#[test]
+fn bernoulli_counts_distinct_known_values() {
+    let mut learner = BernoulliLearner::default();
+    learner
+        .update(&mut "ab".chars(), 1)
+        .update(&mut "bc".chars(), 2);
+    let classifier = learner.make_classifier();
+
+    assert_eq!(classifier.classify(&mut "aabx".chars()).known(), 2);
+}
+
+#[test]
+fn continuous_values_are_always_known() {
+    let classifier = GaussianLearner::default()
+        .update_batch(&mut [[1.0, 2.0], [2.0, 3.0]].iter(), "low")
+        .update_batch(&mut [[7.0, 8.0], [8.0, 9.0]].iter(), "high")
+        .make_classifier();
+
+    assert_eq!(classifier.classify(&[100.0, -100.0]).known(), 2);
+}
+
+#[test]
+fn mixed_counts_known_values() {
+    let classifier = MixedLearner::new(&[Field::Categorical, Field::Gaussian, Field::Tokens])
+        .update(
+            &[
+                Value::Categorical('a'),
+                Value::Gaussian(1.0),
+                Value::Tokens(&['x', 'y']),
+            ],
+            1,
+        )
+        .make_classifier();
+
+    let actual = classifier.classify(&[
+        Value::Categorical('b'),
+        Value::Gaussian(2.0),
+        Value::Tokens(&['x', 'z']),
+    ]);
+
+    assert_eq!(actual.known(), 2);
+}
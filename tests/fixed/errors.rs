@@ -0,0 +1,71 @@
+use naive_bayes::Error;
+use naive_bayes::FixedLearner;
+
+#[test]
+fn empty_learner_is_an_error() {
+    let mut learner = FixedLearner::<char, u8, 2>::default();
+
+    let actual = learner.try_make_classifier();
+
+    assert_eq!(actual.err(), Some(Error::EmptyModel));
+}
+
+#[test]
+fn learner_with_everything_forgotten_is_an_error() {
+    let mut learner = FixedLearner::<char, u8, 2>::default();
+    learner.update(&['a', 'b'], 1);
+    learner.forget(&['a', 'b'], 1).unwrap();
+
+    let actual = learner.try_make_classifier();
+
+    assert_eq!(actual.err(), Some(Error::EmptyModel));
+}
+
+#[test]
+fn invalid_weights_are_errors() {
+    let mut learner = FixedLearner::<char, u8, 2>::default();
+    learner.update(&['a', 'b'], 1);
+    let expected = learner.clone();
+
+    for weight in [f64::NAN, f64::INFINITY, -1.0, 0.0] {
+        assert_eq!(
+            learner.try_update_weighted(&['a', 'b'], 1, weight).err(),
+            Some(Error::InvalidWeight)
+        );
+        assert_eq!(
+            learner
+                .try_update_batch_weighted(&mut [['a', 'b']].iter(), 1, weight)
+                .err(),
+            Some(Error::InvalidWeight)
+        );
+        assert_eq!(
+            learner.forget_weighted(&['a', 'b'], 1, weight).err(),
+            Some(Error::InvalidWeight)
+        );
+    }
+    assert_eq!(learner, expected);
+}
+
+#[test]
+#[should_panic]
+fn nan_weight_panics() {
+    FixedLearner::<char, u8, 2>::default().update_weighted(&['a', 'b'], 1, f64::NAN);
+}
+
+#[test]
+fn trained_learner_makes_classifier() {
+    let mut learner = FixedLearner::<char, u8, 2>::default();
+    learner.update(&['a', 'b'], 1).update(&['b', 'c'], 2);
+
+    let classifier = learner.try_make_classifier().unwrap();
+
+    assert_eq!(
+        classifier
+            .try_classify(&['a', 'b'])
+            .unwrap()
+            .best()
+            .unwrap()
+            .0,
+        1
+    );
+}
@@ -0,0 +1,80 @@
+use naive_bayes::Error;
+use naive_bayes::FixedLearner;
+
+#[test]
+fn forget_reverses_update() {
+    let mut expected = FixedLearner::<char, &'static str, 2>::default();
+    expected.update(&['a', 'b'], "01");
+    let mut actual = FixedLearner::<char, &'static str, 2>::default();
+    actual
+        .update(&['a', 'b'], "01")
+        .update(&['a', 'c'], "02")
+        .update(&['b', 'c'], "02");
+
+    actual
+        .forget(&['a', 'c'], "02")
+        .unwrap()
+        .forget(&['b', 'c'], "02")
+        .unwrap();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn forget_batch_reverses_update_batch() {
+    let mut expected = FixedLearner::<char, &'static str, 1>::default();
+    expected.update_batch(&mut [['a'], ['b']].iter(), "01");
+    let mut actual = FixedLearner::<char, &'static str, 1>::default();
+    actual
+        .update_batch(&mut [['a'], ['b']].iter(), "01")
+        .update_batch(&mut [['b'], ['c'], ['c']].iter(), "02");
+
+    actual
+        .forget_batch(&mut [['c'], ['b'], ['c']].iter(), "02")
+        .unwrap();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn forget_weighted_reverses_update_weighted() {
+    let expected = FixedLearner::<char, &'static str, 1>::default();
+    let mut actual = FixedLearner::<char, &'static str, 1>::default();
+    actual
+        .update_weighted(&['a'], "01", 0.1)
+        .update_weighted(&['a'], "01", 0.2);
+
+    actual
+        .forget_weighted(&['a'], "01", 0.1)
+        .unwrap()
+        .forget_weighted(&['a'], "01", 0.2)
+        .unwrap();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn rejects_forgetting_unlearned_data() {
+    let mut expected = FixedLearner::<char, &'static str, 2>::default();
+    expected.update(&['a', 'b'], "01");
+    let mut actual = expected.clone();
+
+    let result = actual.forget(&['a', 'c'], "01").map(|_| ());
+
+    assert_eq!(result, Err(Error::NegativeCount));
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn rejects_forgetting_data_more_times_than_learned() {
+    let mut expected = FixedLearner::<char, &'static str, 1>::default();
+    expected.update(&['a'], "01");
+    let mut actual = expected.clone();
+
+    let result = actual
+        .forget_batch(&mut [['a'], ['a']].iter(), "01")
+        .map(|_| ());
+
+    assert_eq!(result, Err(Error::NegativeCount));
+    assert_eq!(actual, expected);
+}
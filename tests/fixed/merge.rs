@@ -0,0 +1,40 @@
+use naive_bayes::FixedLearner;
+
+#[test]
+fn merge_is_equivalent_to_sequential_update() {
+    let mut expected = FixedLearner::<char, &'static str, 2>::default();
+    expected
+        .update(&['a', 'b'], "01")
+        .update(&['b', 'c'], "02")
+        .update(&['a', 'c'], "02");
+    let mut shard = FixedLearner::<char, &'static str, 2>::default();
+    shard.update(&['b', 'c'], "02").update(&['a', 'c'], "02");
+    let mut actual = FixedLearner::<char, &'static str, 2>::default();
+    actual.update(&['a', 'b'], "01");
+
+    actual.merge(&shard);
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn sum_is_equivalent_to_sequential_update() {
+    let data = [(['a'], "01"), (['b'], "02"), (['a'], "01"), (['c'], "03")];
+    let mut expected = FixedLearner::<char, &'static str, 1>::default();
+    for (d, h) in &data {
+        expected.update(d, *h);
+    }
+
+    let actual: FixedLearner<char, &'static str, 1> = data
+        .chunks(2)
+        .map(|shard| {
+            let mut learner = FixedLearner::default();
+            for (d, h) in shard {
+                learner.update(d, *h);
+            }
+            learner
+        })
+        .sum();
+
+    assert_eq!(actual, expected);
+}
@@ -1,5 +1,12 @@
 mod adhoc;
 mod batch;
+mod errors;
+mod forget;
 mod input_lengths;
+mod merge;
+mod owned;
 mod online;
+mod smoothing;
 mod unbalanced_training_data;
+mod unseen;
+mod weighted;
@@ -0,0 +1,14 @@
+use naive_bayes::FixedLearner;
+
+#[test]
+fn classifies_borrowed_values_with_owned_keys() {
+    let mut learner = FixedLearner::<String, String, 2>::default();
+    learner
+        .update(&["red".to_owned(), "round".to_owned()], "apple".to_owned())
+        .update(&["yellow".to_owned(), "long".to_owned()], "banana".to_owned());
+    let classifier = learner.make_classifier();
+
+    let actual = classifier.classify_borrowed(&["red", "round"]);
+
+    assert_eq!(actual.best().unwrap().0, "apple");
+}
@@ -0,0 +1,53 @@
+use assert_approx_eq::assert_approx_eq;
+use naive_bayes::FixedClassifier;
+use naive_bayes::FixedLearner;
+use naive_bayes::Smoothing;
+
+#[test]
+fn classifies_seen_values_with_laplace_smoothing() {
+    let classifier = learn(Smoothing::Laplace);
+
+    let actual = classifier.classify(&['a']);
+
+    assert_eq!(actual.best().unwrap().0, "01");
+    // p(01) * p(a|01) = 5/10 * 4/8, p(02) * p(a|02) = 2/10 * 1/5, p(03) * p(a|03) = 3/10 * 1/6
+    let expected = 0.25 / (0.25 + 0.04 + 0.05);
+    assert_approx_eq!(actual.best().unwrap().1, expected, 0.001);
+}
+
+#[test]
+fn classifies_unseen_values_with_laplace_smoothing() {
+    let classifier = learn(Smoothing::Laplace);
+
+    let actual = classifier.classify(&['d']);
+
+    // p(01) * p(d|01) = 5/10 * 1/8, p(02) * p(d|02) = 2/10 * 1/5, p(03) * p(d|03) = 3/10 * 1/6
+    let expected = 0.0625 / (0.0625 + 0.04 + 0.05);
+    assert_eq!(actual.best().unwrap().0, "01");
+    assert_approx_eq!(actual.best().unwrap().1, expected, 0.001);
+}
+
+#[test]
+fn classifies_unseen_values_without_smoothing_using_priors() {
+    let classifier = learn(Smoothing::None);
+
+    let actual = classifier.classify(&['d']);
+
+    assert_eq!(actual.best().unwrap().0, "01");
+    assert_approx_eq!(actual.best().unwrap().1, 0.5, 0.001);
+}
+
+fn learn(smoothing: Smoothing) -> FixedClassifier<char, &'static str, 1> {
+    FixedLearner::default()
+        .update(&['a'], "01")
+        .update(&['a'], "01")
+        .update(&['a'], "01")
+        .update(&['b'], "01")
+        .update(&['c'], "01")
+        .update(&['b'], "02")
+        .update(&['b'], "02")
+        .update(&['b'], "03")
+        .update(&['c'], "03")
+        .update(&['c'], "03")
+        .make_classifier_with_smoothing(smoothing)
+}
@@ -0,0 +1,44 @@
+use assert_approx_eq::assert_approx_eq;
+use naive_bayes::Error;
+use naive_bayes::FixedClassifier;
+use naive_bayes::FixedLearner;
+use naive_bayes::UnseenPolicy;
+
+#[test]
+fn classifies_with_skip_policy() {
+    let classifier = learn().with_unseen_policy(UnseenPolicy::Skip);
+
+    let actual = classifier.classify(&['a', 'z', 'z']);
+
+    assert_eq!(actual.best().unwrap().0, "01");
+    assert_approx_eq!(actual.best().unwrap().1, 0.75, 0.1);
+}
+
+#[test]
+fn fails_with_error_policy() {
+    let classifier = learn().with_unseen_policy(UnseenPolicy::Error);
+
+    let actual = classifier.try_classify(&['a', 'a', 'z']);
+
+    assert_eq!(actual, Err(Error::UnseenValue { position: 2 }));
+}
+
+fn learn() -> FixedClassifier<char, &'static str, 3> {
+    FixedLearner::default()
+        .update(&['a', 'a', 'a'], "01")
+        .update(&['b', 'b', 'b'], "01")
+        .update(&['c', 'c', 'c'], "01")
+        .update(&['a', 'a', 'a'], "01")
+        .update(&['a', 'a', 'a'], "01")
+        .update(&['b', 'b', 'b'], "02")
+        .update(&['b', 'b', 'b'], "02")
+        .update(&['c', 'c', 'c'], "02")
+        .update(&['b', 'b', 'b'], "02")
+        .update(&['a', 'a', 'a'], "02")
+        .update(&['b', 'b', 'b'], "03")
+        .update(&['c', 'c', 'c'], "03")
+        .update(&['c', 'c', 'c'], "03")
+        .update(&['b', 'b', 'b'], "03")
+        .update(&['c', 'c', 'c'], "03")
+        .make_classifier()
+}
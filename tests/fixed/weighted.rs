@@ -0,0 +1,46 @@
+use assert_approx_eq::assert_approx_eq;
+use naive_bayes::FixedLearner;
+
+#[test]
+fn weighted_update_is_equivalent_to_repeated_update() {
+    let mut expected = FixedLearner::<char, &'static str, 2>::default();
+    expected
+        .update(&['a', 'b'], "01")
+        .update(&['a', 'b'], "01")
+        .update(&['a', 'b'], "01")
+        .update(&['b', 'c'], "02");
+    let mut actual = FixedLearner::<char, &'static str, 2>::default();
+    actual
+        .update_weighted(&['a', 'b'], "01", 3.0)
+        .update(&['b', 'c'], "02");
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn weighted_batch_update_is_equivalent_to_repeated_batch_update() {
+    let batch = [['a', 'b'], ['a', 'c']];
+    let mut expected = FixedLearner::<char, &'static str, 2>::default();
+    expected
+        .update_batch(&mut batch.iter(), "01")
+        .update_batch(&mut batch.iter(), "01");
+    let mut actual = FixedLearner::<char, &'static str, 2>::default();
+    actual.update_batch_weighted(&mut batch.iter(), "01", 2.0);
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn down_weighted_instances_have_less_influence() {
+    let classifier = FixedLearner::default()
+        .update(&['a'], "01")
+        .update(&['b'], "01")
+        .update_weighted(&['a'], "02", 0.5)
+        .update_weighted(&['a'], "02", 0.5)
+        .make_classifier();
+
+    let actual = classifier.classify(&['a']);
+
+    // p(01) * p(a|01) = 2/3 * 1/2, p(02) * p(a|02) = 1/3 * 1
+    assert_approx_eq!(actual.best().unwrap().1, 0.5, 0.001);
+}
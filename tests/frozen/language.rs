@@ -0,0 +1,143 @@
+use assert_approx_eq::assert_approx_eq;
+use naive_bayes::FrozenClassifier;
+use naive_bayes::Results;
+use naive_bayes::Scratch;
+use naive_bayes::StreamClassifier;
+use naive_bayes::StreamLearner;
+use naive_bayes::UnseenPolicy;
+use std::fs::File;
+use std::io::Write;
+
+#[test]
+fn classifies_memory_mapped_model() {
+    let path = std::env::temp_dir().join(format!("naive_bayes_{}.nbcm", std::process::id()));
+    File::create(&path)
+        .unwrap()
+        .write_all(&learn().to_frozen_bytes())
+        .unwrap();
+    let file = File::open(&path).unwrap();
+    let map = unsafe { memmap2::Mmap::map(&file).unwrap() };
+
+    let classifier = FrozenClassifier::<&str, u8>::from_bytes(&map).unwrap();
+    let actual = classifier.classify(&mut words(include_str!(
+        "../stream/data/polish/test_pl_01.txt"
+    )));
+
+    assert_eq!(actual.best().unwrap().0, POLISH);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn matches_stream_classifier() {
+    let expected = learn();
+    let bytes = expected.to_frozen_bytes();
+    let actual = FrozenClassifier::<&str, u8>::from_bytes(&bytes).unwrap();
+
+    for text in TEST_DATA {
+        assert_same_best(
+            actual.classify(&mut words(text)),
+            expected.classify_borrowed(&mut words(text)),
+        );
+    }
+}
+
+#[test]
+fn classifies_into_reused_results() {
+    let expected = learn();
+    let bytes = expected.to_frozen_bytes();
+    let actual = FrozenClassifier::<&str, u8>::from_bytes(&bytes).unwrap();
+    let mut scratch = Scratch::new();
+    let mut results = Results::default();
+
+    for text in TEST_DATA {
+        actual.classify_into(&mut scratch, &mut words(text), &mut results);
+        assert_same_best(
+            results.clone(),
+            expected.classify_borrowed(&mut words(text)),
+        );
+    }
+}
+
+#[test]
+fn classifies_borrowed_values_in_single_precision() {
+    let expected = learn();
+    let bytes = expected.to_frozen_bytes();
+    let actual = FrozenClassifier::<&str, u8, f32>::from_bytes(&bytes).unwrap();
+
+    for text in TEST_DATA {
+        let actual = actual.classify_borrowed(&mut words(text));
+        let expected = expected.classify_borrowed(&mut words(text));
+        assert_eq!(actual.best().unwrap().0, expected.best().unwrap().0);
+    }
+}
+
+#[test]
+fn matches_stream_classifier_with_unseen_policy() {
+    for policy in [
+        UnseenPolicy::Penalty(-20.0),
+        UnseenPolicy::Skip,
+        UnseenPolicy::Smoothed,
+    ] {
+        let expected = learn().with_unseen_policy(policy);
+        let bytes = learn().to_frozen_bytes();
+        let actual = FrozenClassifier::<&str, u8>::from_bytes(&bytes)
+            .unwrap()
+            .with_unseen_policy(policy);
+
+        assert_same_best(
+            actual.classify(&mut words("zzz qqq ¿dónde está?")),
+            expected.classify_borrowed(&mut words("zzz qqq ¿dónde está?")),
+        );
+    }
+}
+
+const ENGLISH: u8 = 0;
+const POLISH: u8 = 1;
+const SPANISH: u8 = 2;
+
+const TEST_DATA: [&str; 6] = [
+    include_str!("../stream/data/english/test_en_01.txt"),
+    include_str!("../stream/data/english/test_en_02.txt"),
+    include_str!("../stream/data/polish/test_pl_01.txt"),
+    include_str!("../stream/data/polish/test_pl_02.txt"),
+    include_str!("../stream/data/spanish/test_es_01.txt"),
+    include_str!("../stream/data/spanish/test_es_02.txt"),
+];
+
+fn learn() -> StreamClassifier<String, u8> {
+    let mut learner = StreamLearner::default();
+    learner
+        .update(
+            &mut owned(words(include_str!(
+                "../stream/data/english/train_en_01.txt"
+            ))),
+            ENGLISH,
+        )
+        .update(
+            &mut owned(words(include_str!("../stream/data/polish/train_pl_01.txt"))),
+            POLISH,
+        )
+        .update(
+            &mut owned(words(include_str!(
+                "../stream/data/spanish/train_es_01.txt"
+            ))),
+            SPANISH,
+        );
+    learner.make_classifier()
+}
+
+// Posteriors are normalised in hash order, so they can differ in the last bit.
+fn assert_same_best(actual: Results<u8>, expected: Results<u8>) {
+    let (actual, expected) = (actual.best().unwrap(), expected.best().unwrap());
+    assert_eq!(actual.0, expected.0);
+    assert_approx_eq!(actual.1, expected.1, 1e-12);
+}
+
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|x: char| !x.is_alphabetic())
+        .filter(|x| !x.is_empty())
+}
+
+fn owned<'a>(words: impl Iterator<Item = &'a str>) -> impl Iterator<Item = String> {
+    words.map(str::to_owned)
+}
@@ -0,0 +1,61 @@
+use naive_bayes::Error;
+use naive_bayes::FrozenClassifier;
+use naive_bayes::StreamClassifier;
+use naive_bayes::StreamLearner;
+
+#[test]
+fn rejects_truncated_model() {
+    let bytes = learn().to_frozen_bytes();
+
+    for len in 0..bytes.len() {
+        let actual = FrozenClassifier::<char, u8>::from_bytes(&bytes[..len]);
+
+        assert_eq!(actual.err(), Some(Error::Truncated));
+    }
+}
+
+#[test]
+fn rejects_unsorted_vocabulary() {
+    let mut bytes = learn().to_frozen_bytes();
+    // The vocabulary starts after the header, hypothesis table, two lengths and the offsets of
+    // the three values.
+    let start = 22 + 4 + 2 * (1 + 8 + 8) + 8 + 4 * 4;
+    assert_eq!(&bytes[start..start + 8], &[b'a', 0, 0, 0, b'b', 0, 0, 0]);
+    bytes[start] = b'c';
+
+    let actual = FrozenClassifier::<char, u8>::from_bytes(&bytes);
+
+    assert_eq!(actual.err(), Some(Error::InvalidFormat));
+}
+
+#[test]
+fn rejects_repeated_hypothesis() {
+    let mut bytes = learn().to_frozen_bytes();
+    // The entries start after the vocabulary of three values and the offsets of their entries.
+    let start = 22 + 4 + 2 * (1 + 8 + 8) + 8 + 4 * 4 + 3 * 4 + 4 * 4;
+    assert_eq!(&bytes[start..start + 4], &[0, 0, 0, 0]);
+    assert_eq!(&bytes[start + 12..start + 16], &[1, 0, 0, 0]);
+    bytes[start + 12] = 0;
+
+    let actual = FrozenClassifier::<char, u8>::from_bytes(&bytes);
+
+    assert_eq!(actual.err(), Some(Error::InvalidFormat));
+}
+
+#[test]
+fn rejects_model_file() {
+    let bytes = learn().to_bytes();
+
+    let actual = FrozenClassifier::<char, u8>::from_bytes(&bytes);
+
+    assert_eq!(actual.err(), Some(Error::InvalidFormat));
+}
+
+fn learn() -> StreamClassifier<char, u8> {
+    let mut learner = StreamLearner::default();
+    learner
+        .update(&mut "aab".chars(), 1)
+        .update(&mut "abb".chars(), 1)
+        .update(&mut "bccca".chars(), 2);
+    learner.make_classifier()
+}
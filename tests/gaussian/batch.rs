@@ -0,0 +1,44 @@
+use assert_approx_eq::assert_approx_eq;
+use naive_bayes::GaussianClassifier;
+use naive_bayes::GaussianLearner;
+
+#[test]
+fn classifies_test_case_01() {
+    let classifier = learn();
+
+    let actual = classifier.classify(&[2.0]);
+
+    assert_eq!(actual.best().unwrap().0, "low");
+    assert_approx_eq!(actual.best().unwrap().1, 1.0, 0.01);
+}
+
+#[test]
+fn classifies_midpoint_as_equally_likely() {
+    let classifier = learn();
+
+    let actual = classifier.classify(&[5.0]);
+
+    for (_, p) in actual {
+        assert_approx_eq!(p, 0.5, 0.001);
+    }
+}
+
+#[test]
+fn classifies_with_zero_variance() {
+    let classifier = GaussianLearner::default()
+        .update_batch(&mut [[1.0], [1.0]].iter(), "low")
+        .update_batch(&mut [[3.0], [3.0]].iter(), "high")
+        .make_classifier();
+
+    let actual = classifier.classify(&[1.5]);
+
+    assert_eq!(actual.best().unwrap().0, "low");
+    assert_approx_eq!(actual.best().unwrap().1, 1.0, 0.01);
+}
+
+fn learn() -> GaussianClassifier<&'static str, 1> {
+    GaussianLearner::default()
+        .update_batch(&mut [[1.0], [2.0], [3.0]].iter(), "low")
+        .update_batch(&mut [[7.0], [8.0], [9.0]].iter(), "high")
+        .make_classifier()
+}
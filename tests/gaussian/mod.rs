@@ -0,0 +1,2 @@
+mod batch;
+mod online;
@@ -0,0 +1,62 @@
+use assert_approx_eq::assert_approx_eq;
+use naive_bayes::Error;
+use naive_bayes::GaussianClassifier;
+use naive_bayes::GaussianLearner;
+
+// Test from: https://en.wikipedia.org/wiki/Naive_Bayes_classifier#Person_classification
+#[test]
+fn classifies_test_case_01() {
+    let classifier = learn();
+
+    let actual = classifier.classify(&[6.0, 130.0, 8.0]);
+
+    assert_eq!(actual.best().unwrap().0, "female");
+    assert_approx_eq!(actual.best().unwrap().1, 1.0, 0.01);
+}
+
+#[test]
+fn classifies_test_case_02() {
+    let classifier = learn();
+
+    let actual = classifier.classify(&[5.9, 175.0, 11.0]);
+
+    assert_eq!(actual.best().unwrap().0, "male");
+    assert_approx_eq!(actual.best().unwrap().1, 1.0, 0.01);
+}
+
+#[test]
+fn handles_empty_training_set() {
+    let classifier: GaussianClassifier<&'static str, 3> =
+        GaussianLearner::default().make_classifier();
+
+    let actual = classifier.classify(&[6.0, 130.0, 8.0]);
+
+    assert_eq!(actual.best(), Option::None);
+    assert_eq!(actual.into_iter().count(), 0);
+}
+
+#[test]
+fn invalid_variance_smoothing_is_an_error() {
+    let mut learner = GaussianLearner::default();
+    learner.update(&[6.0, 180.0, 12.0], "male");
+
+    for variance_smoothing in [0.0, -1e-9, f64::INFINITY, f64::NAN] {
+        let actual: Result<GaussianClassifier<&'static str, 3>, Error> =
+            learner.try_make_classifier_with_variance_smoothing(variance_smoothing);
+
+        assert_eq!(actual.err(), Some(Error::InvalidSmoothing));
+    }
+}
+
+fn learn() -> GaussianClassifier<&'static str, 3> {
+    GaussianLearner::default()
+        .update(&[6.0, 180.0, 12.0], "male")
+        .update(&[5.92, 190.0, 11.0], "male")
+        .update(&[5.58, 170.0, 12.0], "male")
+        .update(&[5.92, 165.0, 10.0], "male")
+        .update(&[5.0, 100.0, 6.0], "female")
+        .update(&[5.5, 150.0, 8.0], "female")
+        .update(&[5.42, 130.0, 7.0], "female")
+        .update(&[5.75, 150.0, 9.0], "female")
+        .make_classifier()
+}
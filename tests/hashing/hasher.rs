@@ -0,0 +1,69 @@
+use assert_approx_eq::assert_approx_eq;
+use naive_bayes::FixedLearner;
+use naive_bayes::StreamLearner;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::BuildHasherDefault;
+
+type FixedKeys = BuildHasherDefault<DefaultHasher>;
+
+#[test]
+fn custom_hasher_gives_same_results_as_default_hasher() {
+    let mut expected = StreamLearner::<char, &'static str>::default();
+    let mut actual = StreamLearner::<char, &'static str, FixedKeys>::with_default_hasher();
+    for (stream, hypothesis) in [("aab", "01"), ("bbc", "02"), ("cca", "03")] {
+        expected.update(&mut stream.chars(), hypothesis);
+        actual.update(&mut stream.chars(), hypothesis);
+    }
+
+    let expected: HashMap<_, _> = expected
+        .make_classifier()
+        .classify(&mut "ab".chars())
+        .into_iter()
+        .collect();
+    let actual = actual.make_classifier().classify(&mut "ab".chars());
+
+    for (h, p) in actual {
+        assert_approx_eq!(p, expected[h], 1e-12);
+    }
+}
+
+#[test]
+fn learners_with_custom_hasher_can_be_summed() {
+    let mut learner: FixedLearner<char, &'static str, 1, FixedKeys> = ["01", "02", "01"]
+        .iter()
+        .zip(['a', 'b', 'a'])
+        .map(|(h, d)| {
+            let mut learner = FixedLearner::with_default_hasher();
+            learner.update(&[d], *h);
+            learner
+        })
+        .sum();
+
+    let actual = learner.make_classifier().classify(&['a']);
+
+    assert_eq!(actual.best().unwrap().0, "01");
+}
+
+#[cfg(feature = "fxhash")]
+#[test]
+fn fx_hasher_gives_same_results_as_default_hasher() {
+    use naive_bayes::FxBuildHasher;
+
+    let mut expected = StreamLearner::<&'static str, u8>::default();
+    let mut actual = StreamLearner::<&'static str, u8, FxBuildHasher>::with_default_hasher();
+    for (document, hypothesis) in [("free money now", 1), ("meeting notes attached", 2)] {
+        expected.update(&mut document.split(' '), hypothesis);
+        actual.update(&mut document.split(' '), hypothesis);
+    }
+
+    let expected = expected
+        .make_classifier()
+        .classify(&mut "free notes".split(' '));
+    let actual = actual
+        .make_classifier()
+        .classify(&mut "free notes".split(' '));
+
+    assert_eq!(actual.best().unwrap().0, expected.best().unwrap().0);
+    assert_approx_eq!(actual.best().unwrap().1, expected.best().unwrap().1, 1e-12);
+}
@@ -0,0 +1,2 @@
+mod hasher;
+mod ties;
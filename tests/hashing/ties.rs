@@ -0,0 +1,22 @@
+use naive_bayes::StreamLearner;
+use std::collections::HashSet;
+
+#[test]
+fn best_breaks_ties_in_the_same_way_for_every_classifier() {
+    // Each Learner has differently keyed maps, so hypotheses are iterated in a different order.
+    let actual: HashSet<&str> = (0..20)
+        .map(|_| {
+            StreamLearner::default()
+                .update(&mut ['a'].into_iter(), "01")
+                .update(&mut ['b'].into_iter(), "02")
+                .update(&mut ['c'].into_iter(), "03")
+                .make_classifier()
+                .classify(&mut ['d'].into_iter())
+                .best()
+                .unwrap()
+                .0
+        })
+        .collect();
+
+    assert_eq!(actual.len(), 1);
+}
@@ -0,0 +1,128 @@
+use assert_approx_eq::assert_approx_eq;
+use naive_bayes::Error;
+use naive_bayes::Field;
+use naive_bayes::MixedClassifier;
+use naive_bayes::MixedLearner;
+use naive_bayes::Smoothing;
+use naive_bayes::Value;
+
+const SCHEMA: [Field; 3] = [Field::Categorical, Field::Gaussian, Field::Tokens];
+
+#[test]
+fn classifies_test_case_01() {
+    let classifier = learn();
+
+    let actual = classifier.classify(&[
+        Value::Categorical("red"),
+        Value::Gaussian(150.0),
+        Value::Tokens(&["sweet", "crisp"]),
+    ]);
+
+    assert_eq!(actual.best().unwrap().0, "fruit");
+    assert_approx_eq!(actual.best().unwrap().1, 1.0, 0.01);
+}
+
+#[test]
+fn classifies_test_case_02() {
+    let classifier = learn();
+
+    let actual = classifier.classify(&[
+        Value::Categorical("green"),
+        Value::Gaussian(900.0),
+        Value::Tokens(&["leafy"]),
+    ]);
+
+    assert_eq!(actual.best().unwrap().0, "vegetable");
+    assert_approx_eq!(actual.best().unwrap().1, 1.0, 0.01);
+}
+
+#[test]
+fn combines_evidence_from_all_fields() {
+    let classifier = learn();
+
+    // The colour is more common for vegetables, but the weight and tokens are typical of fruit.
+    let actual = classifier.classify(&[
+        Value::Categorical("green"),
+        Value::Gaussian(160.0),
+        Value::Tokens(&["sweet", "juicy"]),
+    ]);
+
+    assert_eq!(actual.best().unwrap().0, "fruit");
+}
+
+#[test]
+fn fails_when_record_does_not_match_schema() {
+    let classifier = learn();
+
+    let actual = classifier.try_classify(&[
+        Value::Categorical("green"),
+        Value::Tokens(&["sweet"]),
+        Value::Gaussian(160.0),
+    ]);
+
+    assert_eq!(actual, Err(Error::SchemaMismatch { position: 1 }));
+}
+
+#[test]
+fn fails_when_record_has_missing_fields() {
+    let mut learner = MixedLearner::<&'static str, &'static str>::new(&SCHEMA);
+
+    let actual = learner
+        .try_update(&[Value::Categorical("green")], "fruit")
+        .map(|_| ());
+
+    assert_eq!(actual, Err(Error::SchemaMismatch { position: 1 }));
+}
+
+fn learn() -> MixedClassifier<&'static str, &'static str> {
+    MixedLearner::new(&SCHEMA)
+        .update(
+            &[
+                Value::Categorical("red"),
+                Value::Gaussian(180.0),
+                Value::Tokens(&["sweet", "crisp", "round"]),
+            ],
+            "fruit",
+        )
+        .update(
+            &[
+                Value::Categorical("yellow"),
+                Value::Gaussian(120.0),
+                Value::Tokens(&["sweet", "soft"]),
+            ],
+            "fruit",
+        )
+        .update(
+            &[
+                Value::Categorical("green"),
+                Value::Gaussian(150.0),
+                Value::Tokens(&["juicy", "sweet"]),
+            ],
+            "fruit",
+        )
+        .update(
+            &[
+                Value::Categorical("green"),
+                Value::Gaussian(800.0),
+                Value::Tokens(&["leafy", "crisp"]),
+            ],
+            "vegetable",
+        )
+        .update(
+            &[
+                Value::Categorical("green"),
+                Value::Gaussian(1000.0),
+                Value::Tokens(&["leafy", "bitter"]),
+            ],
+            "vegetable",
+        )
+        .update(
+            &[
+                Value::Categorical("orange"),
+                Value::Gaussian(700.0),
+                Value::Tokens(&["crunchy", "root"]),
+            ],
+            "vegetable",
+        )
+        .make_classifier_with_smoothing(Smoothing::Laplace)
+}
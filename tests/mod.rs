@@ -1,2 +1,17 @@
+mod bernoulli;
+mod decision;
 mod fixed;
+mod frozen;
+mod gaussian;
+mod hashing;
+mod mixed;
+mod modelfile;
+mod precision;
+mod quantized;
+mod results;
+mod scratch;
+#[cfg(feature = "rayon")]
+mod parallel;
+#[cfg(feature = "serde")]
+mod serialization;
 mod stream;
@@ -0,0 +1,47 @@
+use naive_bayes::Error;
+use naive_bayes::FixedClassifier;
+use naive_bayes::FixedLearner;
+use naive_bayes::Smoothing;
+use naive_bayes::StreamClassifier;
+use naive_bayes::UnseenPolicy;
+
+#[test]
+fn classifier_round_trips() {
+    let expected = learn()
+        .make_classifier_with_smoothing(Smoothing::Laplace)
+        .with_unseen_policy(UnseenPolicy::Skip);
+    let bytes = expected.to_bytes();
+
+    let actual = FixedClassifier::<u8, bool, 3>::from_bytes(&bytes).unwrap();
+
+    assert_eq!(actual, expected);
+    assert_eq!(actual.classify(&[1, 2, 9]), expected.classify(&[1, 2, 9]));
+}
+
+#[test]
+fn rejects_wrong_number_of_positions() {
+    let bytes = learn().make_classifier().to_bytes();
+
+    let actual = FixedClassifier::<u8, bool, 2>::from_bytes(&bytes);
+
+    assert_eq!(actual, Err(Error::InvalidFormat));
+}
+
+#[test]
+fn rejects_stream_model() {
+    let bytes = learn().make_classifier().to_bytes();
+
+    let actual = StreamClassifier::<u8, bool>::from_bytes(&bytes);
+
+    assert_eq!(actual, Err(Error::InvalidFormat));
+}
+
+fn learn() -> FixedLearner<u8, bool, 3> {
+    let mut learner = FixedLearner::default();
+    learner
+        .update(&[1, 1, 2], true)
+        .update(&[1, 2, 2], true)
+        .update(&[2, 3, 3], false)
+        .update(&[3, 3, 1], false);
+    learner
+}
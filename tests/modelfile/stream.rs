@@ -0,0 +1,142 @@
+use naive_bayes::Error;
+use naive_bayes::StreamClassifier;
+use naive_bayes::StreamLearner;
+use naive_bayes::UnseenPolicy;
+
+#[test]
+fn classifier_round_trips() {
+    let expected = learn()
+        .make_classifier()
+        .with_unseen_policy(UnseenPolicy::Penalty(-4.0));
+    let bytes = expected.to_bytes();
+
+    let actual = StreamClassifier::<char, u8>::from_bytes(&bytes).unwrap();
+
+    assert_eq!(actual, expected);
+    assert_eq!(
+        actual.classify(&mut "abz".chars()),
+        expected.classify(&mut "abz".chars())
+    );
+}
+
+#[test]
+fn rejects_truncated_model() {
+    let bytes = learn().make_classifier().to_bytes();
+
+    for len in 0..bytes.len() {
+        let actual = StreamClassifier::<char, u8>::from_bytes(&bytes[..len]);
+
+        assert_eq!(actual, Err(Error::Truncated));
+    }
+}
+
+#[test]
+fn rejects_trailing_data() {
+    let mut bytes = learn().make_classifier().to_bytes();
+    bytes.push(0);
+
+    let actual = StreamClassifier::<char, u8>::from_bytes(&bytes);
+
+    assert_eq!(actual, Err(Error::InvalidFormat));
+}
+
+#[test]
+fn rejects_bad_magic() {
+    let mut bytes = learn().make_classifier().to_bytes();
+    bytes[0] = b'X';
+
+    let actual = StreamClassifier::<char, u8>::from_bytes(&bytes);
+
+    assert_eq!(actual, Err(Error::InvalidFormat));
+}
+
+#[test]
+fn rejects_newer_version() {
+    let mut bytes = learn().make_classifier().to_bytes();
+    bytes[4..8].copy_from_slice(&2u32.to_le_bytes());
+
+    let actual = StreamClassifier::<char, u8>::from_bytes(&bytes);
+
+    assert_eq!(actual, Err(Error::UnsupportedVersion { version: 2 }));
+}
+
+#[test]
+fn rejects_version_zero() {
+    let mut bytes = learn().make_classifier().to_bytes();
+    bytes[4..8].copy_from_slice(&0u32.to_le_bytes());
+
+    let actual = StreamClassifier::<char, u8>::from_bytes(&bytes);
+
+    assert_eq!(actual, Err(Error::InvalidFormat));
+}
+
+#[test]
+fn rejects_duplicate_hypothesis() {
+    let mut bytes = learn().make_classifier().to_bytes();
+    // The hypothesis table follows the header and its length, with an f64 prior after each.
+    let start = 22 + 4;
+    assert_eq!((bytes[start], bytes[start + 9]), (1, 2));
+    bytes[start + 9] = 1;
+
+    let actual = StreamClassifier::<char, u8>::from_bytes(&bytes);
+
+    assert_eq!(actual, Err(Error::InvalidFormat));
+}
+
+#[test]
+fn rejects_duplicate_value() {
+    let mut bytes = learn().make_classifier().to_bytes();
+    // The vocabulary follows the hypothesis table, the unseen estimates and its length.
+    let start = 22 + 4 + 2 * 9 + 2 * 8 + 4;
+    let row = u32::from_le_bytes(bytes[start + 4..start + 8].try_into().unwrap()) as usize;
+    let next = start + 8 + row * 12;
+    assert_eq!(&bytes[start..start + 4], &[b'a', 0, 0, 0]);
+    assert_eq!(&bytes[next..next + 4], &[b'b', 0, 0, 0]);
+    bytes[next] = b'a';
+
+    let actual = StreamClassifier::<char, u8>::from_bytes(&bytes);
+
+    assert_eq!(actual, Err(Error::InvalidFormat));
+}
+
+#[test]
+fn string_classifier_round_trips() {
+    let mut learner = StreamLearner::<String, String>::default();
+    learner
+        .update(
+            &mut "the cat sat".split(' ').map(String::from),
+            "en".to_string(),
+        )
+        .update(
+            &mut "le chat".split(' ').map(String::from),
+            "fr".to_string(),
+        );
+    let expected = learner.make_classifier();
+    let bytes = expected.to_bytes();
+
+    let actual = StreamClassifier::<String, String>::from_bytes(&bytes).unwrap();
+
+    assert_eq!(actual, expected);
+    assert_eq!(
+        actual.classify_borrowed(&mut ["chat"].into_iter()),
+        expected.classify_borrowed(&mut ["chat"].into_iter())
+    );
+}
+
+#[test]
+fn encoding_does_not_depend_on_hash_order() {
+    let expected = learn().make_classifier().to_bytes();
+
+    let actual = learn().make_classifier().to_bytes();
+
+    assert_eq!(actual, expected);
+}
+
+fn learn() -> StreamLearner<char, u8> {
+    let mut learner = StreamLearner::default();
+    learner
+        .update(&mut "aab".chars(), 1)
+        .update(&mut "abb".chars(), 1)
+        .update(&mut "bccca".chars(), 2);
+    learner
+}
@@ -0,0 +1,31 @@
+use naive_bayes::FixedLearner;
+
+#[test]
+fn par_update_batch_is_equivalent_to_update_batch() {
+    let data: Vec<[u8; 2]> = (0..1000u32)
+        .map(|i| [(i % 7) as u8, (i % 3) as u8])
+        .collect();
+    let mut expected = FixedLearner::<u8, &'static str, 2>::default();
+    expected
+        .update_batch(&mut data.iter(), "01")
+        .update(&[1, 1], "02");
+    let mut actual = FixedLearner::<u8, &'static str, 2>::default();
+    actual.par_update_batch(&data, "01").update(&[1, 1], "02");
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn par_classify_batch_is_equivalent_to_classify() {
+    let classifier = FixedLearner::default()
+        .update_batch(&mut [['a'], ['b'], ['c'], ['a'], ['a']].iter(), "01")
+        .update_batch(&mut [['b'], ['b'], ['c'], ['b'], ['a']].iter(), "02")
+        .update_batch(&mut [['b'], ['c'], ['c'], ['b'], ['c']].iter(), "03")
+        .make_classifier();
+    let data = [['a'], ['b'], ['c'], ['d']];
+
+    let actual = classifier.par_classify_batch(&data);
+
+    let expected: Vec<_> = data.iter().map(|x| classifier.classify(x)).collect();
+    assert_eq!(actual, expected);
+}
@@ -0,0 +1,2 @@
+mod fixed;
+mod stream;
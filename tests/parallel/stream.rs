@@ -0,0 +1,35 @@
+use naive_bayes::StreamLearner;
+
+#[test]
+fn par_update_batch_is_equivalent_to_update() {
+    let documents: Vec<Vec<char>> = ["abca", "bbc", "cab", "aaa"]
+        .iter()
+        .map(|x| x.chars().collect())
+        .collect();
+    let mut expected = StreamLearner::<char, &'static str>::default();
+    for document in &documents {
+        expected.update(&mut document.iter().copied(), "01");
+    }
+    let mut actual = StreamLearner::<char, &'static str>::default();
+    actual.par_update_batch(&documents, "01");
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn par_classify_batch_is_equivalent_to_classify() {
+    let classifier = StreamLearner::default()
+        .update(&mut "abcaa".chars(), "01")
+        .update(&mut "bbcba".chars(), "02")
+        .update(&mut "bccbc".chars(), "03")
+        .make_classifier();
+    let documents = [vec!['a', 'a'], vec!['b'], vec!['c', 'b'], vec![]];
+
+    let actual = classifier.par_classify_batch(&documents);
+
+    let expected: Vec<_> = documents
+        .iter()
+        .map(|x| classifier.classify(&mut x.iter().copied()))
+        .collect();
+    assert_eq!(actual, expected);
+}
@@ -0,0 +1,92 @@
+use assert_approx_eq::assert_approx_eq;
+use naive_bayes::{
+    Field, FixedClassifier, FixedLearner, GaussianLearner, MixedLearner, StreamClassifier,
+    StreamLearner, Value,
+};
+use std::collections::HashMap;
+
+fn stream_classifier() -> StreamClassifier<char, &'static str> {
+    let mut learner = StreamLearner::default();
+    for (stream, hypothesis) in [("aab", "01"), ("bbc", "02"), ("cca", "03")] {
+        learner.update(&mut stream.chars(), hypothesis);
+    }
+    learner.make_classifier()
+}
+
+#[test]
+fn f32_classifier_agrees_with_f64_classifier() {
+    let expected = stream_classifier();
+    let actual: StreamClassifier<char, &'static str, f32> = expected.cast();
+
+    let expected: HashMap<_, _> = expected.classify(&mut "abca".chars()).into_iter().collect();
+    let actual = actual.classify(&mut "abca".chars());
+
+    for (h, p) in actual {
+        assert_approx_eq!(p as f64, expected[h], 1e-5);
+    }
+}
+
+#[test]
+fn f32_posteriors_do_not_underflow_for_long_inputs() {
+    let classifier = stream_classifier().cast::<f32>();
+
+    // The log likelihoods of the hypotheses differ by far more than f32 can represent.
+    let stream = "ab".repeat(10_000);
+    let actual = classifier.classify(&mut stream.chars());
+
+    let sum: f32 = actual.clone().into_iter().map(|(_, p)| p).sum();
+    assert_approx_eq!(sum, 1.0f32, 1e-6);
+    assert_eq!(actual.best().unwrap(), ("01", 1.0));
+}
+
+#[test]
+fn f32_classifier_round_trips_through_model_file() {
+    let mut learner = FixedLearner::default();
+    learner.update(&['a', 'x'], 1u8);
+    learner.update(&['b', 'y'], 2u8);
+    let classifier = learner.make_classifier().cast::<f32>();
+
+    let decoded = FixedClassifier::<char, u8, 2, f32>::from_bytes(&classifier.to_bytes()).unwrap();
+
+    assert_eq!(decoded, classifier);
+}
+
+#[test]
+fn f32_gaussian_classifier_agrees_with_f64_classifier() {
+    let mut learner = GaussianLearner::default();
+    learner.update(&[1.0, 10.0], "low");
+    learner.update(&[2.0, 12.0], "low");
+    learner.update(&[8.0, 30.0], "high");
+    learner.update(&[9.0, 34.0], "high");
+    let expected = learner.make_classifier();
+    let actual = expected.cast::<f32>();
+
+    let expected = expected.classify(&[3.0, 15.0]);
+    let actual = actual.classify(&[3.0, 15.0]);
+
+    assert_eq!(actual.best().unwrap().0, expected.best().unwrap().0);
+    assert_approx_eq!(
+        actual.best().unwrap().1 as f64,
+        expected.best().unwrap().1,
+        1e-5
+    );
+}
+
+#[test]
+fn f32_mixed_classifier_agrees_with_f64_classifier() {
+    let mut learner = MixedLearner::new(&[Field::Categorical, Field::Gaussian]);
+    learner.update(&[Value::Categorical("red"), Value::Gaussian(1.0)], 1u8);
+    learner.update(&[Value::Categorical("red"), Value::Gaussian(2.0)], 1u8);
+    learner.update(&[Value::Categorical("blue"), Value::Gaussian(8.0)], 2u8);
+    learner.update(&[Value::Categorical("blue"), Value::Gaussian(9.0)], 2u8);
+    let expected = learner.make_classifier();
+    let actual = expected.cast::<f32>();
+
+    let record = [Value::Categorical("red"), Value::Gaussian(6.0)];
+    let expected: HashMap<_, _> = expected.classify(&record).into_iter().collect();
+    let actual = actual.classify(&record);
+
+    for (h, p) in actual {
+        assert_approx_eq!(p as f64, expected[&h], 1e-5);
+    }
+}
@@ -0,0 +1 @@
+mod f32;
@@ -0,0 +1,119 @@
+use naive_bayes::QuantizedClassifier;
+use naive_bayes::Smoothing;
+use naive_bayes::StreamLearner;
+use naive_bayes::UnseenPolicy;
+
+#[test]
+fn classifies_like_full_precision_classifier() {
+    let full = learn().make_classifier();
+    let quantized: QuantizedClassifier<u32, u8, u8> = full.quantize();
+
+    let report = quantized.report(&full, &test_data()).unwrap();
+
+    assert_eq!(report.samples, 6);
+    assert_eq!(report.full_accuracy, 1.0);
+    assert_eq!(report.quantized_accuracy, 1.0);
+    assert_eq!(report.agreement, 1.0);
+    assert_eq!(report.accuracy_delta(), 0.0);
+}
+
+#[test]
+fn more_buckets_give_smaller_errors() {
+    let full = learn().make_classifier_with_smoothing(Smoothing::Laplace);
+    let u8_report = full
+        .quantize::<u8>()
+        .report(&full, &short_test_data())
+        .unwrap();
+    let u16_report = full
+        .quantize::<u16>()
+        .report(&full, &short_test_data())
+        .unwrap();
+
+    assert!(u16_report.max_probability_error < u8_report.max_probability_error);
+    assert!(u16_report.max_probability_error < 1e-3);
+}
+
+#[test]
+fn learner_makes_quantized_classifier() {
+    let expected = learn()
+        .make_classifier_with_smoothing(Smoothing::Laplace)
+        .quantize::<u16>();
+
+    let actual = learn().make_quantized_classifier::<u16>(Smoothing::Laplace);
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn applies_unseen_policy() {
+    let classifier = learn()
+        .make_quantized_classifier::<u8>(Smoothing::None)
+        .with_unseen_policy(UnseenPolicy::Error);
+
+    assert!(classifier.try_classify(&mut to_codes("東京")).is_err());
+}
+
+const ENGLISH: u8 = 0;
+const POLISH: u8 = 1;
+const SPANISH: u8 = 2;
+
+fn test_data() -> Vec<(Vec<u32>, u8)> {
+    [
+        (
+            include_str!("../stream/data/english/test_en_01.txt"),
+            ENGLISH,
+        ),
+        (
+            include_str!("../stream/data/english/test_en_02.txt"),
+            ENGLISH,
+        ),
+        (include_str!("../stream/data/polish/test_pl_01.txt"), POLISH),
+        (include_str!("../stream/data/polish/test_pl_02.txt"), POLISH),
+        (
+            include_str!("../stream/data/spanish/test_es_01.txt"),
+            SPANISH,
+        ),
+        (
+            include_str!("../stream/data/spanish/test_es_02.txt"),
+            SPANISH,
+        ),
+    ]
+    .into_iter()
+    .map(|(text, hypothesis)| (to_codes(text).collect(), hypothesis))
+    .collect()
+}
+
+// Short texts, whose posteriors are not all 0 or 1.
+fn short_test_data() -> Vec<(Vec<u32>, u8)> {
+    [
+        ("the", ENGLISH),
+        ("nie", POLISH),
+        ("que", SPANISH),
+        ("a", SPANISH),
+    ]
+    .into_iter()
+    .map(|(text, hypothesis)| (to_codes(text).collect(), hypothesis))
+    .collect()
+}
+
+fn learn() -> StreamLearner<u32, u8> {
+    let mut learner = StreamLearner::default();
+    learner
+        .update(
+            &mut to_codes(include_str!("../stream/data/english/train_en_01.txt")),
+            ENGLISH,
+        )
+        .update(
+            &mut to_codes(include_str!("../stream/data/polish/train_pl_01.txt")),
+            POLISH,
+        )
+        .update(
+            &mut to_codes(include_str!("../stream/data/spanish/train_es_01.txt")),
+            SPANISH,
+        );
+    learner
+}
+
+fn to_codes(text: &str) -> impl Iterator<Item = u32> + '_ {
+    text.chars().filter(|x| x.is_alphabetic()).map(|x| x as u32)
+}
@@ -0,0 +1,55 @@
+use assert_approx_eq::assert_approx_eq;
+use naive_bayes::StreamLearner;
+
+fn learner() -> StreamLearner<char, &'static str> {
+    let mut learner = StreamLearner::default();
+    for (stream, hypothesis) in [("aab", "01"), ("bbc", "02"), ("cca", "03")] {
+        learner.update(&mut stream.chars(), hypothesis);
+    }
+    learner
+}
+
+#[test]
+fn reports_accuracy_of_both_classifiers() {
+    let full = learner().make_classifier();
+    let quantized = full.quantize::<u8>();
+
+    // "ab" is classified as "01" by both, so the label "02" is wrong for both.
+    let test = [("ab", "01"), ("bc", "02"), ("ab", "02"), ("ca", "03")]
+        .map(|(stream, hypothesis)| (stream.chars().collect::<Vec<_>>(), hypothesis));
+    let report = quantized.report(&full, &test).unwrap();
+
+    assert_eq!(report.samples, 4);
+    assert_approx_eq!(report.full_accuracy, 0.75);
+    assert_approx_eq!(report.quantized_accuracy, 0.75);
+    assert_approx_eq!(report.agreement, 1.0);
+}
+
+#[test]
+fn reports_zero_rates_for_empty_test_set() {
+    let full = learner().make_classifier();
+    let test: [(Vec<char>, &str); 0] = [];
+
+    let report = full.quantize::<u16>().report(&full, &test).unwrap();
+
+    assert_eq!(report.samples, 0);
+    assert_eq!(report.full_accuracy, 0.0);
+    assert_eq!(report.quantized_accuracy, 0.0);
+    assert_eq!(report.agreement, 0.0);
+    assert_eq!(report.max_probability_error, 0.0);
+}
+
+#[test]
+fn classifies_model_with_single_likelihood() {
+    let mut learner = StreamLearner::default();
+    learner.update(&mut "a".chars(), "01");
+    learner.update(&mut "b".chars(), "02");
+    let full = learner.make_classifier();
+
+    let actual = full.quantize::<u8>().classify(&mut "a".chars());
+
+    assert_eq!(
+        actual.best().unwrap().0,
+        full.classify(&mut "a".chars()).best().unwrap().0
+    );
+}
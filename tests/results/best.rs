@@ -0,0 +1,82 @@
+use naive_bayes::{Results, StreamLearner};
+use std::collections::HashMap;
+
+// Results in which every hypothesis is equally probable.
+fn tied() -> Results<u32> {
+    let mut learner = StreamLearner::default();
+    for hypothesis in 0..20u32 {
+        learner.update(&mut "ab".chars(), hypothesis);
+    }
+    learner.make_classifier().classify(&mut "ab".chars())
+}
+
+#[test]
+fn nan_never_wins() {
+    let results: Results<char> = [('a', f64::NAN), ('b', 0.25), ('c', f64::NAN), ('d', 0.0)]
+        .into_iter()
+        .collect();
+
+    assert_eq!(results.best(), Some(('b', 0.25)));
+    assert_eq!(results.best_unique(), Some(('b', 0.25)));
+    assert_eq!(results.ranked()[..2], [('b', 0.25), ('d', 0.0)]);
+    assert!(results.ranked()[2..].iter().all(|(_, p)| p.is_nan()));
+}
+
+#[test]
+fn all_nan_has_a_best_but_no_unique_best() {
+    let results: Results<char> = [('a', f64::NAN), ('b', f64::NAN)].into_iter().collect();
+
+    assert!(results.best().unwrap().1.is_nan());
+    assert_eq!(results.best_unique(), None);
+}
+
+#[test]
+fn ties_are_broken_by_the_given_order() {
+    let results = tied();
+
+    assert_eq!(results.best_by(Ord::cmp).unwrap().0, 0);
+    assert_eq!(results.best_by(|lhs, rhs| rhs.cmp(lhs)).unwrap().0, 19);
+}
+
+#[test]
+fn ties_are_broken_by_prior() {
+    let results: Results<&str> = [("spam", 0.5), ("ham", 0.5), ("eggs", 0.0)]
+        .into_iter()
+        .collect();
+    let priors: HashMap<&str, f64> = [("spam", 0.2), ("ham", 0.7), ("eggs", 0.1)]
+        .into_iter()
+        .collect();
+
+    let actual = results.best_by(|lhs, rhs| priors[rhs].partial_cmp(&priors[lhs]).unwrap());
+
+    assert_eq!(actual, Some(("ham", 0.5)));
+}
+
+#[test]
+fn best_is_the_same_for_every_tie() {
+    let expected = tied().best();
+
+    for _ in 0..5 {
+        assert_eq!(tied().best(), expected);
+    }
+}
+
+#[test]
+fn best_unique_is_none_when_tied() {
+    assert_eq!(tied().best_unique(), None);
+    let results: Results<char> = [('a', 0.4), ('b', 0.4), ('c', 0.2)].into_iter().collect();
+
+    assert_eq!(results.best_unique(), None);
+    assert_eq!(Results::<char>::default().best_unique(), None);
+}
+
+#[test]
+fn best_unique_is_best_when_not_tied() {
+    let results: Results<char> = [('a', 0.3), ('b', 0.5), ('c', 0.2)].into_iter().collect();
+
+    assert_eq!(results.best_unique(), Some(('b', 0.5)));
+    assert_eq!(results.best_unique(), results.best());
+    let single: Results<char> = [('a', 1.0)].into_iter().collect();
+
+    assert_eq!(single.best_unique(), Some(('a', 1.0)));
+}
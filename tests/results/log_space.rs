@@ -0,0 +1,81 @@
+use assert_approx_eq::assert_approx_eq;
+use naive_bayes::{GaussianLearner, Results, StreamLearner};
+
+fn learn() -> StreamLearner<char, &'static str> {
+    let mut learner = StreamLearner::default();
+    learner
+        .update(&mut "aab".chars(), "first")
+        .update(&mut "ab".chars(), "second")
+        .update(&mut "bc".chars(), "third");
+    learner
+}
+
+fn assert_log_space_agrees(results: &Results<&str>) {
+    let sum: f64 = results.log_joints().map(|(_, x)| x.exp2()).sum();
+    assert_approx_eq!(results.log_normaliser(), sum.log2());
+    for (h, p) in results {
+        let log_posterior = results.log_posterior(h).unwrap();
+        assert_approx_eq!(
+            log_posterior,
+            results.log_joint(h).unwrap() - results.log_normaliser()
+        );
+        assert_approx_eq!(log_posterior.exp2(), *p);
+    }
+}
+
+#[test]
+fn log_posteriors_agree_with_posteriors() {
+    let results = learn().make_classifier().classify(&mut "abc".chars());
+
+    assert_log_space_agrees(&results);
+}
+
+#[test]
+fn log_posteriors_agree_with_posteriors_of_continuous_data() {
+    let classifier = GaussianLearner::default()
+        .update_batch(&mut [[1.0], [2.0], [3.0]].iter(), "low")
+        .update_batch(&mut [[7.0], [8.0], [9.0]].iter(), "high")
+        .make_classifier();
+
+    let results = classifier.classify(&[4.0]);
+
+    assert_log_space_agrees(&results);
+}
+
+#[test]
+fn underflowed_hypotheses_can_be_compared() {
+    let input = "a".repeat(5000);
+
+    let results = learn().make_classifier().classify(&mut input.chars());
+
+    assert_eq!(results.get(&"second"), Some(0.0));
+    assert_eq!(results.get(&"third"), Some(0.0));
+    let second = results.log_posterior(&"second").unwrap();
+    let third = results.log_posterior(&"third").unwrap();
+    assert!(second.is_finite() && third.is_finite());
+    assert!(second > third);
+    assert_approx_eq!(results.log_posterior(&"first").unwrap(), 0.0);
+    let ranked: Vec<&str> = results.ranked().into_iter().map(|(h, _)| h).collect();
+    assert_eq!(ranked, ["first", "second", "third"]);
+}
+
+#[test]
+fn empty_input_has_no_log_scores() {
+    let results = learn().make_classifier().classify(&mut "".chars());
+
+    assert_eq!(results.log_normaliser(), f64::NEG_INFINITY);
+    for (h, _) in &results {
+        assert_eq!(results.log_joint(h), Some(f64::NEG_INFINITY));
+        assert_eq!(results.log_posterior(h), Some(f64::NEG_INFINITY));
+    }
+    assert_eq!(results.log_posterior(&"fourth"), None);
+}
+
+#[test]
+fn collected_results_are_normalised() {
+    let results: Results<char> = [('a', 0.25), ('b', 0.75)].into_iter().collect();
+
+    assert_eq!(results.log_normaliser(), 0.0);
+    assert_eq!(results.log_joint(&'a'), Some(-2.0));
+    assert_eq!(results.log_posterior(&'a'), Some(-2.0));
+}
@@ -0,0 +1,4 @@
+mod best;
+mod log_space;
+mod ranked;
+mod uncertainty;
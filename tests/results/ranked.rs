@@ -0,0 +1,75 @@
+use assert_approx_eq::assert_approx_eq;
+use naive_bayes::{Results, StreamLearner};
+
+fn classify(input: &str) -> Results<char> {
+    let mut learner = StreamLearner::default();
+    learner
+        .update(&mut "aab".chars(), 'x')
+        .update(&mut "bbc".chars(), 'y')
+        .update(&mut "cca".chars(), 'z');
+    learner.make_classifier().classify(&mut input.chars())
+}
+
+// Results in which every hypothesis is equally probable.
+fn tied() -> Results<u32> {
+    let mut learner = StreamLearner::default();
+    for hypothesis in 0..20u32 {
+        learner.update(&mut "ab".chars(), hypothesis);
+    }
+    learner.make_classifier().classify(&mut "ab".chars())
+}
+
+#[test]
+fn ranked_is_in_descending_order() {
+    let results = classify("aaab");
+
+    let actual = results.ranked();
+
+    assert_eq!(actual.len(), 3);
+    assert_eq!(actual[0].0, 'x');
+    assert!(actual.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    assert_eq!(actual[0], results.best().unwrap());
+}
+
+#[test]
+fn top_k_is_a_prefix_of_ranked() {
+    let results = tied();
+    let ranked = results.ranked();
+
+    for k in 0..=ranked.len() {
+        assert_eq!(results.top_k(k), ranked[..k]);
+    }
+    assert_eq!(results.top_k(ranked.len() + 1), ranked);
+}
+
+#[test]
+fn ties_are_ranked_deterministically() {
+    let ranked = tied().ranked();
+
+    for _ in 0..5 {
+        assert_eq!(tied().ranked(), ranked);
+    }
+    assert_eq!(tied().best().unwrap(), ranked[0]);
+}
+
+#[test]
+fn inspects_without_consuming() {
+    let results = classify("aaab");
+
+    assert_eq!(results.len(), 3);
+    assert!(!results.is_empty());
+    assert_eq!(results.get(&'w'), None);
+    assert_approx_eq!(results.iter().map(|(_, p)| *p).sum::<f64>(), 1.0);
+    for (h, p) in &results {
+        assert_eq!(results.get(h), Some(*p));
+    }
+}
+
+#[test]
+fn default_results_are_empty() {
+    let results: Results<char> = Results::default();
+
+    assert!(results.is_empty());
+    assert_eq!(results.ranked(), vec![]);
+    assert_eq!(results.top_k(3), vec![]);
+}
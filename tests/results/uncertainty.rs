@@ -0,0 +1,69 @@
+use assert_approx_eq::assert_approx_eq;
+use naive_bayes::{Results, StreamLearner};
+
+fn results(values: &[(char, f64)]) -> Results<char> {
+    values.iter().copied().collect()
+}
+
+#[test]
+fn entropy_is_in_bits() {
+    assert_approx_eq!(results(&[('a', 0.5), ('b', 0.5)]).entropy(), 1.0);
+    let uniform: Results<u8> = (0..4).map(|h| (h, 0.25)).collect();
+    assert_approx_eq!(uniform.entropy(), 2.0);
+    assert_approx_eq!(results(&[('a', 1.0), ('b', 0.0)]).entropy(), 0.0);
+}
+
+#[test]
+fn margin_is_between_the_best_two() {
+    let results = results(&[('a', 0.1), ('b', 0.6), ('c', 0.3)]);
+
+    assert_approx_eq!(results.margin(), 0.3);
+    assert_approx_eq!(results.confidence_ratio(), 2.0);
+}
+
+#[test]
+fn single_hypothesis_is_certain() {
+    let results = results(&[('a', 1.0)]);
+
+    assert_eq!(results.entropy(), 0.0);
+    assert_eq!(results.margin(), 1.0);
+    assert_eq!(results.confidence_ratio(), f64::INFINITY);
+}
+
+#[test]
+fn empty_results_have_no_confidence() {
+    let results: Results<char> = Results::default();
+
+    assert_eq!(results.entropy(), 0.0);
+    assert_eq!(results.margin(), 0.0);
+    assert_eq!(results.confidence_ratio(), 1.0);
+}
+
+#[test]
+fn ties_have_no_margin() {
+    let results = results(&[('a', 0.4), ('b', 0.4), ('c', 0.2)]);
+
+    assert_eq!(results.margin(), 0.0);
+    assert_eq!(results.confidence_ratio(), 1.0);
+}
+
+#[test]
+fn ratio_does_not_underflow() {
+    let mut learner = StreamLearner::default();
+    learner
+        .update(&mut "aab".chars(), "first")
+        .update(&mut "ab".chars(), "second");
+    let classifier = learner.make_classifier();
+    let input = "a".repeat(500);
+
+    let results = classifier.classify(&mut input.chars());
+
+    assert_eq!(results.get(&"second"), Some(0.0));
+    assert_approx_eq!(results.margin(), 1.0);
+    let ratio = results.confidence_ratio();
+    assert!(ratio.is_finite() && ratio > 2f64.powi(100));
+    let results = classifier.classify(&mut "".chars());
+
+    assert_eq!(results.margin(), 0.0);
+    assert_eq!(results.confidence_ratio(), 1.0);
+}
@@ -0,0 +1,81 @@
+use naive_bayes::{FixedLearner, Results, Scratch, StreamLearner, UnseenPolicy};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+// Count the allocations made by each thread, so that tests running in parallel do not
+// interfere with each other.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+#[test]
+fn stream_classify_into_does_not_allocate_once_grown() {
+    let mut learner = StreamLearner::default();
+    learner
+        .update(&mut "the cat sat".split(' '), "cats")
+        .update(&mut "the dog ran".split(' '), "dogs");
+    let classifier = learner
+        .make_classifier()
+        .with_unseen_policy(UnseenPolicy::Smoothed);
+    let mut scratch = Scratch::new();
+    let mut results = Results::default();
+    classifier.classify_into(&mut scratch, &mut "the cat".split(' '), &mut results);
+
+    let actual = allocations(|| {
+        for input in ["the cat ran far", "a dog sat", ""] {
+            classifier.classify_into(&mut scratch, &mut input.split(' '), &mut results);
+        }
+    });
+
+    assert_eq!(actual, 0);
+    assert_eq!(results, classifier.classify(&mut "".split(' ')));
+}
+
+#[test]
+fn fixed_classify_into_does_not_allocate_once_grown() {
+    let mut learner = FixedLearner::default();
+    learner
+        .update(&['a', 'b'], 1u8)
+        .update(&['b', 'c'], 2)
+        .update(&['c', 'a'], 3);
+    let classifier = learner.make_classifier();
+    let mut scratch = Scratch::new();
+    let mut results = Results::default();
+    classifier.classify_into(&mut scratch, &['a', 'b'], &mut results);
+
+    let actual = allocations(|| {
+        for input in [['b', 'c'], ['c', 'a'], ['a', 'c']] {
+            classifier.classify_into(&mut scratch, &input, &mut results);
+        }
+    });
+
+    assert_eq!(actual, 0);
+    assert_eq!(results, classifier.classify(&['a', 'c']));
+}
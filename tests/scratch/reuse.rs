@@ -0,0 +1,75 @@
+use naive_bayes::{Error, FixedLearner, Results, Scratch, StreamLearner, UnseenPolicy};
+
+fn learn() -> StreamLearner<char, &'static str> {
+    let mut learner = StreamLearner::default();
+    learner
+        .update(&mut "aab".chars(), "first")
+        .update(&mut "bbc".chars(), "second")
+        .update(&mut "cca".chars(), "third");
+    learner
+}
+
+#[test]
+fn classify_into_agrees_with_classify() {
+    let classifier = learn().make_classifier();
+    let mut scratch = Scratch::new();
+    let mut results = Results::default();
+
+    for input in ["a", "abc", "ccc", "bab", ""] {
+        classifier.classify_into(&mut scratch, &mut input.chars(), &mut results);
+
+        assert_eq!(results, classifier.classify(&mut input.chars()));
+    }
+}
+
+#[test]
+fn skipped_values_are_not_scored() {
+    let classifier = learn()
+        .make_classifier()
+        .with_unseen_policy(UnseenPolicy::Skip);
+    let mut scratch = Scratch::new();
+    let mut results = Results::default();
+
+    for input in ["axb", "xyz", "ab"] {
+        classifier.classify_into(&mut scratch, &mut input.chars(), &mut results);
+
+        assert_eq!(results, classifier.classify(&mut input.chars()));
+    }
+}
+
+#[test]
+fn scratch_is_reused_across_classifiers() {
+    let stream = learn().make_classifier();
+    let mut learner = FixedLearner::default();
+    learner
+        .update(&['a', 'b'], "first")
+        .update(&['b', 'c'], "second");
+    let fixed = learner.make_classifier();
+    let mut scratch = Scratch::new();
+    let mut results = Results::default();
+
+    stream.classify_into(&mut scratch, &mut "abc".chars(), &mut results);
+    fixed.classify_into(&mut scratch, &['a', 'b'], &mut results);
+
+    assert_eq!(results, fixed.classify(&['a', 'b']));
+
+    stream.classify_into(&mut scratch, &mut "cc".chars(), &mut results);
+
+    assert_eq!(results, stream.classify(&mut "cc".chars()));
+}
+
+#[test]
+fn results_are_unchanged_on_error() {
+    let classifier = learn()
+        .make_classifier()
+        .with_unseen_policy(UnseenPolicy::Error);
+    let mut scratch = Scratch::new();
+    let mut results = Results::default();
+    classifier.classify_into(&mut scratch, &mut "ab".chars(), &mut results);
+    let expected = results.clone();
+
+    let actual = classifier.try_classify_into(&mut scratch, &mut "abx".chars(), &mut results);
+
+    assert_eq!(actual, Err(Error::UnseenValue { position: 2 }));
+    assert_eq!(results, expected);
+}
@@ -0,0 +1,197 @@
+use naive_bayes::BernoulliClassifier;
+use naive_bayes::BernoulliLearner;
+use naive_bayes::Error;
+use naive_bayes::Field;
+use naive_bayes::FixedClassifier;
+use naive_bayes::FixedLearner;
+use naive_bayes::GaussianClassifier;
+use naive_bayes::GaussianLearner;
+use naive_bayes::MixedClassifier;
+use naive_bayes::MixedLearner;
+use naive_bayes::QuantizedClassifier;
+use naive_bayes::Smoothing;
+use naive_bayes::StreamClassifier;
+use naive_bayes::StreamLearner;
+use naive_bayes::Value as FieldValue;
+use serde_json::Value;
+
+#[test]
+fn classifier_missing_prior_is_an_error() {
+    let mut learner = StreamLearner::<char, u8>::default();
+    learner
+        .update(&mut "aab".chars(), 1)
+        .update(&mut "bcc".chars(), 2);
+    let json = remove_entry(&learner.make_classifier(), "log_priors", 2);
+
+    let actual = serde_json::from_value::<StreamClassifier<char, u8>>(json);
+
+    assert!(actual.is_err());
+}
+
+#[test]
+fn quantized_classifier_missing_prior_is_an_error() {
+    let mut learner = StreamLearner::<char, u8>::default();
+    learner
+        .update(&mut "aab".chars(), 1)
+        .update(&mut "bcc".chars(), 2);
+    let classifier: QuantizedClassifier<char, u8, u8> =
+        learner.make_quantized_classifier(Smoothing::None);
+    let json = remove_entry(&classifier, "log_priors", 2);
+
+    let actual = serde_json::from_value::<QuantizedClassifier<char, u8, u8>>(json);
+
+    assert!(actual.is_err());
+}
+
+#[test]
+fn learner_missing_hypothesis_count_is_an_error() {
+    let mut learner = FixedLearner::<char, u8, 2>::default();
+    learner.update(&['a', 'b'], 1).update(&['b', 'c'], 2);
+    let json = remove_entry(&learner, "count_hypotheses", 2);
+
+    let mut actual: FixedLearner<char, u8, 2> = serde_json::from_value(json).unwrap();
+
+    assert_eq!(
+        actual.try_make_classifier().err(),
+        Some(Error::InconsistentModel)
+    );
+}
+
+#[test]
+fn classifier_missing_unseen_estimate_is_an_error() {
+    let mut learner = FixedLearner::<char, u8, 2>::default();
+    learner.update(&['a', 'b'], 1).update(&['b', 'c'], 2);
+    let mut json = serde_json::to_value(learner.make_classifier()).unwrap();
+    json["log_unseen"][1]
+        .as_array_mut()
+        .unwrap()
+        .retain(|entry| entry[0] != 2);
+
+    let actual = serde_json::from_value::<FixedClassifier<char, u8, 2>>(json);
+
+    assert!(actual.is_err());
+}
+
+#[test]
+fn learner_negative_count_is_an_error() {
+    let mut learner = StreamLearner::<char, u8>::default();
+    learner
+        .update(&mut "aab".chars(), 1)
+        .update(&mut "bcc".chars(), 2);
+    let mut json = serde_json::to_value(&learner).unwrap();
+    json["count_joint"][0][1] = (-1.0).into();
+
+    let actual = serde_json::from_value::<StreamLearner<char, u8>>(json);
+
+    assert!(actual.is_err());
+}
+
+#[test]
+fn learner_negative_total_is_an_error() {
+    let mut learner = FixedLearner::<char, u8, 2>::default();
+    learner.update(&['a', 'b'], 1).update(&['b', 'c'], 2);
+    let mut json = serde_json::to_value(&learner).unwrap();
+    json["count_total"] = (-2.0).into();
+
+    let actual = serde_json::from_value::<FixedLearner<char, u8, 2>>(json);
+
+    assert!(actual.is_err());
+}
+
+#[test]
+fn mixed_classifier_missing_unseen_estimate_is_an_error() {
+    let mut learner = MixedLearner::<char, u8>::new(&[Field::Categorical, Field::Tokens]);
+    learner
+        .update(
+            &[FieldValue::Categorical('a'), FieldValue::Tokens(&['x'])],
+            1,
+        )
+        .update(
+            &[FieldValue::Categorical('b'), FieldValue::Tokens(&['y'])],
+            2,
+        );
+    let mut json = serde_json::to_value(learner.make_classifier()).unwrap();
+    json["fields"][1]["Tokens"][1]
+        .as_array_mut()
+        .unwrap()
+        .retain(|entry| entry[0] != 2);
+
+    let actual = serde_json::from_value::<MixedClassifier<char, u8>>(json);
+
+    assert!(actual.is_err());
+}
+
+#[test]
+fn gaussian_classifier_missing_prior_is_an_error() {
+    let json = remove_entry(&learn_gaussian(), "log_priors", 2);
+
+    let actual = serde_json::from_value::<GaussianClassifier<u8, 2>>(json);
+
+    assert!(actual.is_err());
+}
+
+#[test]
+fn gaussian_classifier_zero_variance_is_an_error() {
+    let mut json = serde_json::to_value(learn_gaussian()).unwrap();
+    json["parameters"][1][0][1][1] = 0.0.into();
+
+    let actual = serde_json::from_value::<GaussianClassifier<u8, 2>>(json);
+
+    assert!(actual.is_err());
+}
+
+#[test]
+fn gaussian_classifier_negative_variance_is_an_error() {
+    let mut json = serde_json::to_value(learn_gaussian()).unwrap();
+    json["parameters"][0][1][1][1] = (-1.0).into();
+
+    let actual = serde_json::from_value::<GaussianClassifier<u8, 2>>(json);
+
+    assert!(actual.is_err());
+}
+
+#[test]
+fn bernoulli_classifier_missing_unseen_ratio_is_an_error() {
+    let json = remove_entry(&learn_bernoulli(), "log_unseen_ratios", 2);
+
+    let actual = serde_json::from_value::<BernoulliClassifier<char, u8>>(json);
+
+    assert!(actual.is_err());
+}
+
+#[test]
+fn bernoulli_classifier_missing_prior_is_an_error() {
+    let json = remove_entry(&learn_bernoulli(), "log_priors", 1);
+
+    let actual = serde_json::from_value::<BernoulliClassifier<char, u8>>(json);
+
+    assert!(actual.is_err());
+}
+
+fn learn_gaussian() -> GaussianClassifier<u8, 2> {
+    let mut learner = GaussianLearner::<u8, 2>::default();
+    learner
+        .update(&[1.0, 2.0], 1)
+        .update(&[1.5, 2.5], 1)
+        .update(&[4.0, 8.0], 2)
+        .update(&[5.0, 9.0], 2);
+    learner.make_classifier()
+}
+
+fn learn_bernoulli() -> BernoulliClassifier<char, u8> {
+    let mut learner = BernoulliLearner::<char, u8>::default();
+    learner
+        .update(&mut "aab".chars(), 1)
+        .update(&mut "bcc".chars(), 2);
+    learner.make_classifier()
+}
+
+// Serialize a model and remove the entry for a hypothesis from one of its maps.
+fn remove_entry<T: serde::Serialize>(model: &T, field: &str, hypothesis: u8) -> Value {
+    let mut json = serde_json::to_value(model).unwrap();
+    json[field]
+        .as_array_mut()
+        .unwrap()
+        .retain(|entry| entry[0] != hypothesis);
+    json
+}
@@ -0,0 +1,51 @@
+use naive_bayes::FixedClassifier;
+use naive_bayes::FixedLearner;
+use naive_bayes::Smoothing;
+use naive_bayes::UnseenPolicy;
+
+#[test]
+fn learner_round_trips_and_continues_training() {
+    let mut expected = learn();
+    let json = serde_json::to_string(&expected).unwrap();
+
+    let mut actual: FixedLearner<char, u8, 3> = serde_json::from_str(&json).unwrap();
+    actual.update(&['c', 'a', 'b'], 3);
+    expected.update(&['c', 'a', 'b'], 3);
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn classifier_round_trips() {
+    let expected = learn()
+        .make_classifier_with_smoothing(Smoothing::Laplace)
+        .with_unseen_policy(UnseenPolicy::Skip);
+    let json = serde_json::to_string(&expected).unwrap();
+
+    let actual: FixedClassifier<char, u8, 3> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(actual, expected);
+    assert_eq!(
+        actual.classify(&['a', 'b', 'z']),
+        expected.classify(&['a', 'b', 'z'])
+    );
+}
+
+#[test]
+fn rejects_wrong_number_of_positions() {
+    let json = serde_json::to_string(&learn()).unwrap();
+
+    let actual = serde_json::from_str::<FixedLearner<char, u8, 2>>(&json);
+
+    assert!(actual.is_err());
+}
+
+fn learn() -> FixedLearner<char, u8, 3> {
+    let mut learner = FixedLearner::default();
+    learner
+        .update(&['a', 'a', 'b'], 1)
+        .update(&['a', 'b', 'b'], 1)
+        .update(&['b', 'c', 'c'], 2)
+        .update(&['c', 'c', 'a'], 2);
+    learner
+}
@@ -0,0 +1,4 @@
+mod corrupt;
+mod fixed;
+mod stream;
+mod types;
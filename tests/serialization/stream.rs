@@ -0,0 +1,101 @@
+use naive_bayes::ComplementClassifier;
+use naive_bayes::QuantizedClassifier;
+use naive_bayes::Results;
+use naive_bayes::Smoothing;
+use naive_bayes::StreamClassifier;
+use naive_bayes::StreamLearner;
+
+#[test]
+fn learner_round_trips_and_continues_training() {
+    let mut expected = learn();
+    let json = serde_json::to_string(&expected).unwrap();
+
+    let mut actual: StreamLearner<char, u8> = serde_json::from_str(&json).unwrap();
+    actual.update(&mut "cab".chars(), 3);
+    expected.update(&mut "cab".chars(), 3);
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn classifier_round_trips() {
+    let expected = learn().make_classifier();
+    let json = serde_json::to_string(&expected).unwrap();
+
+    let actual: StreamClassifier<char, u8> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn quantized_classifier_round_trips() {
+    let expected = learn().make_classifier().quantize::<u8>();
+    let json = serde_json::to_string(&expected).unwrap();
+
+    let actual: QuantizedClassifier<char, u8, u8> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(actual, expected);
+    assert_eq!(
+        actual.classify(&mut "abc".chars()),
+        expected.classify(&mut "abc".chars())
+    );
+}
+
+#[test]
+fn normalised_complement_classifier_of_one_hypothesis_round_trips() {
+    // The only hypothesis has an empty complement, so every weight and its norm are 0.
+    let mut learner = StreamLearner::<char, u8>::default();
+    learner.update(&mut "aa".chars(), 1);
+    let expected = learner.make_complement_classifier(Smoothing::Laplace, true);
+    let json = serde_json::to_string(&expected).unwrap();
+
+    let actual: ComplementClassifier<char, u8> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn results_round_trip() {
+    let expected = learn().make_classifier().classify(&mut "abc".chars());
+    let json = serde_json::to_string(&expected).unwrap();
+
+    let actual: Results<u8> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn serialization_does_not_depend_on_hash_order() {
+    let expected = serde_json::to_string(&learn().make_classifier()).unwrap();
+
+    let actual = serde_json::to_string(&learn().make_classifier()).unwrap();
+
+    assert_eq!(actual, expected);
+}
+
+fn learn() -> StreamLearner<char, u8> {
+    let mut learner = StreamLearner::default();
+    learner
+        .update(&mut "aab".chars(), 1)
+        .update(&mut "abb".chars(), 1)
+        .update(&mut "bccca".chars(), 2);
+    learner
+}
+
+#[test]
+fn serialization_order_is_the_same_in_every_build() {
+    let mut learner = StreamLearner::<char, u8>::default();
+    for hypothesis in 1..=5 {
+        learner.update(&mut "ab".chars(), hypothesis);
+    }
+
+    let actual: Vec<u64> = serde_json::to_value(&learner).unwrap()["count_hypotheses"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry[0].as_u64().unwrap())
+        .collect();
+
+    // Hypotheses are ordered by their 64 bit FNV-1a hash.
+    assert_eq!(actual, vec![5, 4, 1, 3, 2]);
+}
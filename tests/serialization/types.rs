@@ -0,0 +1,75 @@
+use naive_bayes::BernoulliClassifier;
+use naive_bayes::BernoulliLearner;
+use naive_bayes::Field;
+use naive_bayes::GaussianClassifier;
+use naive_bayes::GaussianLearner;
+use naive_bayes::MixedClassifier;
+use naive_bayes::MixedLearner;
+use naive_bayes::Value;
+
+#[test]
+fn gaussian_learner_round_trips() {
+    let mut learner = GaussianLearner::<u8, 2>::default();
+    learner
+        .update(&[1.0, 2.0], 1)
+        .update(&[1.5, 2.5], 1)
+        .update(&[8.0, 9.0], 2);
+    let json = serde_json::to_string(&learner).unwrap();
+
+    let actual: GaussianLearner<u8, 2> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(actual, learner);
+    let classifier = learner.make_classifier();
+    let json = serde_json::to_string(&classifier).unwrap();
+    let actual: GaussianClassifier<u8, 2> = serde_json::from_str(&json).unwrap();
+    assert_eq!(actual, classifier);
+}
+
+#[test]
+fn bernoulli_learner_round_trips() {
+    let mut learner = BernoulliLearner::<char, u8>::default();
+    learner
+        .update(&mut "ab".chars(), 1)
+        .update(&mut "bc".chars(), 2);
+    let json = serde_json::to_string(&learner).unwrap();
+
+    let actual: BernoulliLearner<char, u8> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(actual, learner);
+    let classifier = learner.make_classifier();
+    let json = serde_json::to_string(&classifier).unwrap();
+    let actual: BernoulliClassifier<char, u8> = serde_json::from_str(&json).unwrap();
+    assert_eq!(actual, classifier);
+}
+
+#[test]
+fn mixed_learner_round_trips() {
+    let mut learner =
+        MixedLearner::<char, u8>::new(&[Field::Categorical, Field::Gaussian, Field::Tokens]);
+    learner
+        .update(
+            &[
+                Value::Categorical('a'),
+                Value::Gaussian(1.0),
+                Value::Tokens(&['x', 'y']),
+            ],
+            1,
+        )
+        .update(
+            &[
+                Value::Categorical('b'),
+                Value::Gaussian(5.0),
+                Value::Tokens(&['y', 'z']),
+            ],
+            2,
+        );
+    let json = serde_json::to_string(&learner).unwrap();
+
+    let actual: MixedLearner<char, u8> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(actual, learner);
+    let classifier = learner.make_classifier();
+    let json = serde_json::to_string(&classifier).unwrap();
+    let actual: MixedClassifier<char, u8> = serde_json::from_str(&json).unwrap();
+    assert_eq!(actual, classifier);
+}
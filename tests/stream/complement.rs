@@ -0,0 +1,103 @@
+use naive_bayes::Error;
+use naive_bayes::Smoothing;
+use naive_bayes::StreamLearner;
+use naive_bayes::UnseenPolicy;
+
+#[test]
+fn multinomial_classifier_favours_majority_class() {
+    let mut learner = learn();
+    let classifier = learner.make_classifier_with_smoothing(Smoothing::Laplace);
+
+    let actual = classifier.classify(&mut ['b'].into_iter());
+
+    assert_eq!(actual.best().unwrap().0, "majority");
+}
+
+#[test]
+fn classifies_minority_class() {
+    let mut learner = learn();
+    let classifier = learner.make_complement_classifier(Smoothing::Laplace, false);
+
+    let actual = classifier.classify(&mut ['b'].into_iter());
+
+    // p(b|~majority) = 3/6, p(b|~minority) = 21/103
+    assert_eq!(actual.best().unwrap().0, "minority");
+}
+
+#[test]
+fn classifies_minority_class_with_normalised_weights() {
+    let mut learner = learn();
+    let classifier = learner.make_complement_classifier(Smoothing::Laplace, true);
+
+    let actual = classifier.classify(&mut ['b', 'c'].into_iter());
+
+    assert_eq!(actual.best().unwrap().0, "minority");
+}
+
+#[test]
+fn classifies_majority_class() {
+    let mut learner = learn();
+    let classifier = learner.make_complement_classifier(Smoothing::Laplace, true);
+
+    let actual = classifier.classify(&mut ['a', 'a', 'b'].into_iter());
+
+    assert_eq!(actual.best().unwrap().0, "majority");
+}
+
+#[test]
+fn value_seen_with_one_hypothesis_is_evidence_for_it() {
+    let mut learner = learn();
+    let classifier = learner.make_complement_classifier(Smoothing::None, false);
+
+    // c was not seen outside minority, so its weight is the unseen weight of ~minority.
+    let actual = classifier.classify(&mut ['c'].into_iter());
+
+    assert_eq!(actual.best().unwrap().0, "minority");
+}
+
+#[test]
+fn values_seen_by_no_hypothesis_are_skipped() {
+    let mut learner = learn();
+    let classifier = learner.make_complement_classifier(Smoothing::Laplace, true);
+
+    let actual = classifier.classify(&mut ['b', 'z'].into_iter());
+
+    assert_eq!(actual, classifier.classify(&mut ['b'].into_iter()));
+}
+
+#[test]
+fn values_seen_by_no_hypothesis_can_be_rejected() {
+    let mut learner = learn();
+    let classifier = learner
+        .make_complement_classifier(Smoothing::Laplace, true)
+        .with_unseen_policy(UnseenPolicy::Error)
+        .unwrap();
+
+    let actual = classifier.try_classify(&mut ['b', 'z'].into_iter());
+
+    assert_eq!(actual, Err(Error::UnseenValue { position: 1 }));
+}
+
+#[test]
+fn penalties_are_not_supported() {
+    let mut learner = learn();
+    let classifier = learner.make_complement_classifier(Smoothing::Laplace, true);
+
+    for policy in [UnseenPolicy::PLACEHOLDER, UnseenPolicy::Smoothed] {
+        let actual = classifier.clone().with_unseen_policy(policy);
+
+        assert_eq!(actual.err(), Some(Error::UnsupportedPolicy));
+    }
+}
+
+fn learn() -> StreamLearner<char, &'static str> {
+    let mut learner = StreamLearner::default();
+    for _ in 0..10 {
+        learner.update(
+            &mut ['a', 'a', 'a', 'a', 'a', 'a', 'a', 'a', 'b', 'b'].into_iter(),
+            "majority",
+        );
+    }
+    learner.update(&mut ['b', 'b', 'c'].into_iter(), "minority");
+    learner
+}
@@ -0,0 +1,96 @@
+use naive_bayes::BernoulliLearner;
+use naive_bayes::Error;
+use naive_bayes::Field;
+use naive_bayes::GaussianLearner;
+use naive_bayes::MixedLearner;
+use naive_bayes::Smoothing;
+use naive_bayes::StreamLearner;
+
+#[test]
+fn empty_learners_are_errors() {
+    let mut stream = StreamLearner::<char, u8>::default();
+    let mut bernoulli = BernoulliLearner::<char, u8>::default();
+    let mut gaussian = GaussianLearner::<u8, 2>::default();
+    let mut mixed = MixedLearner::<char, u8>::new(&[Field::Categorical]);
+
+    assert_eq!(stream.try_make_classifier().err(), Some(Error::EmptyModel));
+    assert_eq!(
+        stream
+            .try_make_complement_classifier(Smoothing::Laplace, true)
+            .err(),
+        Some(Error::EmptyModel)
+    );
+    assert_eq!(
+        bernoulli.try_make_classifier().err(),
+        Some(Error::EmptyModel)
+    );
+    assert_eq!(
+        gaussian.try_make_classifier().err(),
+        Some(Error::EmptyModel)
+    );
+    assert_eq!(mixed.try_make_classifier().err(), Some(Error::EmptyModel));
+}
+
+#[test]
+fn empty_learner_still_makes_classifier() {
+    let classifier = StreamLearner::<char, u8>::default().make_classifier();
+
+    let actual = classifier.classify(&mut "abc".chars());
+
+    assert_eq!(actual.best(), None);
+}
+
+#[test]
+fn learner_of_empty_streams_is_an_error() {
+    let mut learner = StreamLearner::<char, u8>::default();
+    learner
+        .update(&mut "".chars(), 1)
+        .update(&mut "".chars(), 2);
+
+    assert_eq!(learner.try_make_classifier().err(), Some(Error::EmptyData));
+}
+
+#[test]
+fn invalid_smoothing_is_an_error() {
+    let mut learner = StreamLearner::<char, u8>::default();
+    learner.update(&mut "ab".chars(), 1);
+
+    for alpha in [f64::NAN, f64::INFINITY, -1.0, 0.0] {
+        for smoothing in [
+            Smoothing::Lidstone(alpha),
+            Smoothing::Vocabulary { alpha, size: 256 },
+        ] {
+            assert_eq!(
+                learner.try_make_classifier_with_smoothing(smoothing).err(),
+                Some(Error::InvalidSmoothing)
+            );
+            assert_eq!(
+                learner
+                    .try_make_complement_classifier(smoothing, false)
+                    .err(),
+                Some(Error::InvalidSmoothing)
+            );
+        }
+    }
+}
+
+#[test]
+fn invalid_weights_are_errors() {
+    let mut learner = StreamLearner::<char, u8>::default();
+    learner.update(&mut "ab".chars(), 1);
+    let expected = learner.clone();
+
+    for weight in [f64::NAN, f64::NEG_INFINITY, -0.5, 0.0] {
+        assert_eq!(
+            learner
+                .try_update_weighted(&mut "ab".chars(), 1, weight)
+                .err(),
+            Some(Error::InvalidWeight)
+        );
+        assert_eq!(
+            learner.forget_weighted(&mut "ab".chars(), 1, weight).err(),
+            Some(Error::InvalidWeight)
+        );
+    }
+    assert_eq!(learner, expected);
+}
@@ -0,0 +1,81 @@
+use assert_approx_eq::assert_approx_eq;
+use naive_bayes::Error;
+use naive_bayes::StreamLearner;
+
+#[test]
+fn forget_reverses_update() {
+    let mut expected = StreamLearner::<char, &'static str>::default();
+    expected.update(&mut ['a', 'b'].into_iter(), "01");
+    let mut actual = StreamLearner::<char, &'static str>::default();
+    actual
+        .update(&mut ['a', 'b'].into_iter(), "01")
+        .update(&mut ['a', 'b', 'c'].into_iter(), "02");
+
+    actual
+        .forget(&mut ['c', 'b', 'a'].into_iter(), "02")
+        .unwrap();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn classifies_without_forgotten_document() {
+    let mut learner = StreamLearner::<char, &'static str>::default();
+    learner
+        .update(&mut ['a', 'a', 'b'].into_iter(), "01")
+        .update(&mut ['b', 'b'].into_iter(), "02")
+        // Mislabeled document.
+        .update(&mut ['a', 'a', 'a', 'a'].into_iter(), "02");
+
+    learner
+        .forget(&mut ['a', 'a', 'a', 'a'].into_iter(), "02")
+        .unwrap();
+    let actual = learner.make_classifier().classify(&mut ['a'].into_iter());
+
+    assert_eq!(actual.best().unwrap().0, "01");
+    assert_approx_eq!(actual.best().unwrap().1, 1.0, 0.001);
+}
+
+#[test]
+fn rejects_forgetting_unlearned_data() {
+    let mut expected = StreamLearner::<char, &'static str>::default();
+    expected.update(&mut ['a', 'b'].into_iter(), "01");
+    let mut actual = expected.clone();
+
+    let result = actual
+        .forget(&mut ['a', 'b', 'b'].into_iter(), "01")
+        .map(|_| ());
+
+    assert_eq!(result, Err(Error::NegativeCount));
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn forgets_part_of_small_weights() {
+    let mut expected = StreamLearner::<char, &'static str>::default();
+    expected.update_weighted(&mut ['a', 'b'].into_iter(), "01", 1e-10);
+    let mut actual = StreamLearner::<char, &'static str>::default();
+    actual
+        .update_weighted(&mut ['a', 'b'].into_iter(), "01", 1e-10)
+        .update_weighted(&mut ['a', 'b'].into_iter(), "01", 1e-10);
+
+    actual
+        .forget_weighted(&mut ['a', 'b'].into_iter(), "01", 1e-10)
+        .unwrap();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn rejects_forgetting_more_than_small_weights() {
+    let mut expected = StreamLearner::<char, &'static str>::default();
+    expected.update_weighted(&mut ['a', 'b'].into_iter(), "01", 1e-10);
+    let mut actual = expected.clone();
+
+    let result = actual
+        .forget_weighted(&mut ['a', 'b'].into_iter(), "01", 5e-10)
+        .map(|_| ());
+
+    assert_eq!(result, Err(Error::NegativeCount));
+    assert_eq!(actual, expected);
+}
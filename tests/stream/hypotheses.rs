@@ -0,0 +1,68 @@
+use assert_approx_eq::assert_approx_eq;
+use naive_bayes::StreamLearner;
+use naive_bayes::UnseenPolicy;
+
+#[test]
+fn classifiers_do_not_depend_on_training_order() {
+    let training = [("aab", 1u16), ("bbc", 2), ("cca", 3), ("abc", 4)];
+    let mut expected = StreamLearner::default();
+    for (stream, hypothesis) in training {
+        expected.update(&mut stream.chars(), hypothesis);
+    }
+    let mut actual = StreamLearner::default();
+    for (stream, hypothesis) in training.into_iter().rev() {
+        actual.update(&mut stream.chars(), hypothesis);
+    }
+
+    assert_eq!(actual.make_classifier(), expected.make_classifier());
+}
+
+#[test]
+fn classifies_many_hypotheses() {
+    let mut learner = StreamLearner::default();
+    for hypothesis in 0..1000u32 {
+        let stream = [hypothesis, hypothesis + 1000];
+        learner.update(&mut stream.iter().copied(), hypothesis);
+    }
+    let classifier = learner.make_classifier();
+
+    for hypothesis in [0, 1, 499, 999] {
+        let actual = classifier.classify(&mut [hypothesis, hypothesis + 1000].iter().copied());
+
+        let (best, p) = actual.best().unwrap();
+        assert_eq!(best, hypothesis);
+        assert_approx_eq!(p, 1.0);
+    }
+}
+
+#[test]
+fn empty_stream_has_zero_posteriors() {
+    let mut learner = StreamLearner::default();
+    learner
+        .update(&mut "ab".chars(), 1)
+        .update(&mut "bc".chars(), 2);
+    let classifier = learner.make_classifier();
+
+    let actual: Vec<(u8, f64)> = classifier.classify(&mut "".chars()).into_iter().collect();
+
+    assert_eq!(actual.len(), 2);
+    assert!(actual.iter().all(|(_, p)| *p == 0.0));
+}
+
+#[test]
+fn stream_of_skipped_values_has_zero_posteriors() {
+    let mut learner = StreamLearner::default();
+    learner
+        .update(&mut "ab".chars(), 1)
+        .update(&mut "bc".chars(), 2);
+    let classifier = learner
+        .make_classifier()
+        .with_unseen_policy(UnseenPolicy::Skip);
+
+    let actual: Vec<(u8, f64)> = classifier
+        .classify(&mut "xyz".chars())
+        .into_iter()
+        .collect();
+
+    assert!(actual.iter().all(|(_, p)| *p == 0.0));
+}
@@ -154,8 +154,5 @@ fn learn() -> StreamClassifier<char, &'static str> {
 }
 
 fn to_ascii(original: &'static str) -> impl Iterator<Item = char> {
-    original
-        .chars()
-        .into_iter()
-        .filter(|x| x.is_ascii_alphabetic())
+    original.chars().filter(|x| x.is_ascii_alphabetic())
 }
@@ -62,14 +62,14 @@ fn classifies_spanish_test_data_01() {
     assert_approx_eq!(actual.best().unwrap().1, 1.0, 0.1);
 }
 
-const TRAIN_EN_01: &'static str = "Article 2
+const TRAIN_EN_01: &str = "Article 2
     Everyone is entitled to all the rights and freedoms set forth in this Declaration,
     without distinction of any kind, such as race, colour, sex, language, religion, political or
     other opinion, national or social origin, property, birth or other status. Furthermore, no
     distinction shall be made on the basis of the political, jurisdictional or international status
     of the country or territory to which a person belongs, whether it be independent, trust,
     non-self-governing or under any other limitation of sovereignty.";
-const TRAIN_PL_01: &'static str = "Artykuł 2
+const TRAIN_PL_01: &str = "Artykuł 2
     Każdy człowiek posiada wszystkie prawa i wolności zawarte w niniejszej Deklaracji
     bez względu na jakiekolwiek różnice rasy, koloru, płci, języka, wyznania, poglądów politycznych i
     innych, narodowości, pochodzenia społecznego, majątku, urodzenia lub jakiegokolwiek innego stanu.
@@ -77,7 +77,7 @@ const TRAIN_PL_01: &'static str = "Artykuł 2
     międzynarodowej kraju lub obszaru, do którego dana osoba przynależy, bez względu na to, czy
     dany kraj lub obszar jest niepodległy, czy też podlega systemowi powiernictwa, nie rządzi
     się samodzielnie lub jest w jakikolwiek sposób ograniczony w swej niepodległości.";
-const TRAIN_ES_01: &'static str = "Artículo 2
+const TRAIN_ES_01: &str = "Artículo 2
     Toda persona tiene los derechos y libertades proclamados en esta Declaración,
     sin distinción alguna de raza, color, sexo, idioma, religión, opinión política
     o de cualquier otra índole, origen nacional o social, posición económica,
@@ -88,13 +88,13 @@ const TRAIN_ES_01: &'static str = "Artículo 2
     administración fiduciaria, no autónomo o sometido a cualquier otra limitación
     de soberanía.";
 
-const TEST_EN_01: &'static str = "Article 1
+const TEST_EN_01: &str = "Article 1
     All human beings are born free and equal in dignity and rights. They are endowed with reason and
     conscience and should act towards one another in a spirit of brotherhood.";
-const TEST_PL_01: &'static str = "Artykuł 1
+const TEST_PL_01: &str = "Artykuł 1
     Wszyscy ludzie rodzą się wolni i równi pod względem swej godności i swych praw. Są oni obdarzeni
     rozumem i sumieniem i powinni postępować wobec innych w duchu braterstwa.";
-const TEST_ES_01: &'static str = "Artículo 1
+const TEST_ES_01: &str = "Artículo 1
     Todos los seres humanos nacen libres e iguales en dignidad y derechos y, dotados como están de
     razón y conciencia, deben comportarse fraternalmente los unos con los otros.";
 
@@ -108,8 +108,5 @@ fn learn() -> StreamClassifier<char, &'static str> {
 }
 
 fn to_ascii(original: &'static str) -> impl Iterator<Item = char> {
-    original
-        .chars()
-        .into_iter()
-        .filter(|x| x.is_ascii_alphabetic())
+    original.chars().filter(|x| x.is_ascii_alphabetic())
 }
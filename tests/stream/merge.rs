@@ -0,0 +1,33 @@
+use naive_bayes::StreamLearner;
+
+#[test]
+fn merge_is_equivalent_to_sequential_update() {
+    let mut expected = StreamLearner::<char, &'static str>::default();
+    expected
+        .update(&mut "abca".chars(), "01")
+        .update(&mut "bbc".chars(), "02");
+    let mut shard = StreamLearner::<char, &'static str>::default();
+    shard.update(&mut "bbc".chars(), "02");
+    let mut actual = StreamLearner::<char, &'static str>::default();
+    actual.update(&mut "abca".chars(), "01");
+
+    actual.merge(&shard);
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn add_is_equivalent_to_sequential_update() {
+    let mut expected = StreamLearner::<char, &'static str>::default();
+    expected
+        .update(&mut "abca".chars(), "01")
+        .update(&mut "bbc".chars(), "01");
+    let mut lhs = StreamLearner::<char, &'static str>::default();
+    lhs.update(&mut "abca".chars(), "01");
+    let mut rhs = StreamLearner::<char, &'static str>::default();
+    rhs.update(&mut "bbc".chars(), "01");
+
+    let actual = lhs + rhs;
+
+    assert_eq!(actual, expected);
+}
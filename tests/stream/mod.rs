@@ -1,5 +1,15 @@
 mod adhoc;
+mod complement;
+mod errors;
 mod food_documents;
+mod forget;
+mod hypotheses;
 mod large_unbalanced_data;
 mod medium_balanced_data;
+mod merge;
+mod owned;
+mod smoothing;
+#[allow(clippy::module_inception)]
 mod stream;
+mod unseen;
+mod weighted;
@@ -0,0 +1,57 @@
+use assert_approx_eq::assert_approx_eq;
+use naive_bayes::BernoulliLearner;
+use naive_bayes::StreamClassifier;
+use naive_bayes::StreamLearner;
+
+#[test]
+fn classifies_borrowed_words_with_owned_keys() {
+    let classifier = learn();
+
+    let actual = classifier.classify_borrowed(&mut "the sauce was spicy".split(' '));
+
+    assert_eq!(actual.best().unwrap().0, "food");
+}
+
+#[test]
+fn borrowed_and_owned_classification_agree() {
+    let classifier = learn();
+    let text = "a wet and windy day with spicy rain";
+
+    let expected = classifier.classify(&mut text.split(' ').map(str::to_owned));
+    let actual = classifier.classify_borrowed(&mut text.split(' '));
+
+    assert_eq!(actual.best().unwrap().0, expected.best().unwrap().0);
+    assert_approx_eq!(actual.best().unwrap().1, expected.best().unwrap().1);
+}
+
+#[test]
+fn bernoulli_classifies_borrowed_words_with_owned_keys() {
+    let mut learner = BernoulliLearner::<String, String>::default();
+    learner
+        .update(&mut words("cheap pills online"), "spam".to_owned())
+        .update(&mut words("meeting notes attached"), "ham".to_owned());
+    let classifier = learner.make_classifier();
+
+    let actual = classifier.classify_borrowed(&mut "cheap pills".split(' '));
+
+    assert_eq!(actual.best().unwrap().0, "spam");
+}
+
+fn learn() -> StreamClassifier<String, String> {
+    let mut learner = StreamLearner::<String, String>::default();
+    learner
+        .update(
+            &mut words("the curry was hot and spicy with a rich sauce"),
+            "food".to_owned(),
+        )
+        .update(
+            &mut words("the day was wet and windy with heavy rain"),
+            "weather".to_owned(),
+        );
+    learner.make_classifier()
+}
+
+// Read words at runtime into owned keys, as they would be from a file.
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(' ').map(str::to_owned)
+}
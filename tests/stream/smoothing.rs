@@ -0,0 +1,59 @@
+use assert_approx_eq::assert_approx_eq;
+use naive_bayes::Smoothing;
+use naive_bayes::StreamClassifier;
+use naive_bayes::StreamLearner;
+
+#[test]
+fn classifies_with_laplace_smoothing() {
+    let classifier = learn(Smoothing::Laplace);
+
+    let actual = classifier.classify(&mut ['a'].iter());
+
+    // p(a|01) = 4/8, p(a|02) = 2/8, p(a|03) = 1/8
+    assert_eq!(actual.best().unwrap().0, "01");
+    assert_approx_eq!(actual.best().unwrap().1, 4.0 / 7.0, 0.001);
+}
+
+#[test]
+fn classifies_with_lidstone_smoothing() {
+    let classifier = learn(Smoothing::Lidstone(0.5));
+
+    let actual = classifier.classify(&mut ['a'].iter());
+
+    // p(a|01) = 3.5/6.5, p(a|02) = 1.5/6.5, p(a|03) = 0.5/6.5
+    assert_eq!(actual.best().unwrap().0, "01");
+    assert_approx_eq!(actual.best().unwrap().1, 3.5 / 5.5, 0.001);
+}
+
+#[test]
+fn classifies_with_vocabulary_smoothing() {
+    let classifier = learn(Smoothing::Vocabulary {
+        alpha: 1.0,
+        size: 5,
+    });
+
+    let actual = classifier.classify(&mut ['c'].iter());
+
+    // p(c|01) = 2/10, p(c|02) = 2/10, p(c|03) = 4/10
+    assert_eq!(actual.best().unwrap().0, "03");
+    assert_approx_eq!(actual.best().unwrap().1, 0.5, 0.001);
+}
+
+#[test]
+fn classifies_unseen_values_without_smoothing_using_priors() {
+    let classifier = learn(Smoothing::None);
+
+    let actual = classifier.classify(&mut ['z'].iter());
+
+    for (_, p) in actual {
+        assert_approx_eq!(p, 1.0 / 3.0, 0.001);
+    }
+}
+
+fn learn(smoothing: Smoothing) -> StreamClassifier<&'static char, &'static str> {
+    StreamLearner::default()
+        .update(&mut ['a', 'a', 'a', 'b', 'c'].iter(), "01")
+        .update(&mut ['b', 'b', 'c', 'b', 'a'].iter(), "02")
+        .update(&mut ['b', 'c', 'c', 'b', 'c'].iter(), "03")
+        .make_classifier_with_smoothing(smoothing)
+}
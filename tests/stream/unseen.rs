@@ -0,0 +1,65 @@
+use assert_approx_eq::assert_approx_eq;
+use naive_bayes::Error;
+use naive_bayes::Smoothing;
+use naive_bayes::StreamClassifier;
+use naive_bayes::StreamLearner;
+use naive_bayes::UnseenPolicy;
+
+#[test]
+fn classifies_with_smoothed_policy() {
+    let classifier = learn().with_unseen_policy(UnseenPolicy::Smoothed);
+
+    let actual = classifier.classify(&mut ['a'].iter());
+
+    // p(a|01) = 4/8, p(a|02) = 2/8, p(a|03) = 1/8
+    assert_eq!(actual.best().unwrap().0, "01");
+    assert_approx_eq!(actual.best().unwrap().1, 4.0 / 7.0, 0.001);
+}
+
+#[test]
+fn classifies_with_penalty_policy() {
+    let classifier = learn().with_unseen_policy(UnseenPolicy::PLACEHOLDER);
+
+    let actual = classifier.classify(&mut ['a'].iter());
+
+    // p(a|01) = 4/8, p(a|02) = 2/8, p(a|03) = 2^-53
+    assert_eq!(actual.best().unwrap().0, "01");
+    assert_approx_eq!(actual.best().unwrap().1, 2.0 / 3.0, 0.001);
+}
+
+#[test]
+fn classifies_with_skip_policy() {
+    let classifier = learn().with_unseen_policy(UnseenPolicy::Skip);
+
+    let actual = classifier.classify(&mut ['a', 'x', 'y', 'z'].iter());
+
+    assert_eq!(actual.best().unwrap().0, "01");
+    assert_approx_eq!(actual.best().unwrap().1, 4.0 / 7.0, 0.001);
+}
+
+#[test]
+fn fails_with_error_policy() {
+    let classifier = learn().with_unseen_policy(UnseenPolicy::Error);
+
+    let actual = classifier.try_classify(&mut ['a', 'z'].iter());
+
+    assert_eq!(actual, Err(Error::UnseenValue { position: 1 }));
+}
+
+#[test]
+fn classifies_seen_values_with_error_policy() {
+    let classifier = learn().with_unseen_policy(UnseenPolicy::Error);
+
+    let actual = classifier.try_classify(&mut ['a'].iter()).unwrap();
+
+    assert_eq!(actual.best().unwrap().0, "01");
+    assert_approx_eq!(actual.best().unwrap().1, 4.0 / 7.0, 0.001);
+}
+
+fn learn() -> StreamClassifier<&'static char, &'static str> {
+    StreamLearner::default()
+        .update(&mut ['a', 'a', 'a', 'b', 'c'].iter(), "01")
+        .update(&mut ['b', 'b', 'c', 'b', 'a'].iter(), "02")
+        .update(&mut ['b', 'c', 'c', 'b', 'c'].iter(), "03")
+        .make_classifier_with_smoothing(Smoothing::Laplace)
+}
@@ -0,0 +1,32 @@
+use assert_approx_eq::assert_approx_eq;
+use naive_bayes::StreamLearner;
+
+#[test]
+fn weighted_update_is_equivalent_to_repeated_update() {
+    let mut expected = StreamLearner::<char, &'static str>::default();
+    expected
+        .update(&mut ['a', 'b'].into_iter(), "01")
+        .update(&mut ['a', 'b'].into_iter(), "01")
+        .update(&mut ['b', 'c'].into_iter(), "02");
+    let mut actual = StreamLearner::<char, &'static str>::default();
+    actual
+        .update_weighted(&mut ['a', 'b'].into_iter(), "01", 2.0)
+        .update(&mut ['b', 'c'].into_iter(), "02");
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn rebalancing_weights_remove_prior_bias() {
+    let classifier = StreamLearner::default()
+        .update(&mut ['a', 'b', 'a', 'b'].into_iter(), "01")
+        .update_weighted(&mut ['a', 'c'].into_iter(), "02", 2.0)
+        .make_classifier();
+
+    let actual = classifier.classify(&mut ['a'].into_iter());
+
+    // p(01) * p(a|01) = 1/2 * 1/2, p(02) * p(a|02) = 1/2 * 1/2
+    for (_, p) in actual {
+        assert_approx_eq!(p, 0.5, 0.001);
+    }
+}